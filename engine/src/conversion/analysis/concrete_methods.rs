@@ -0,0 +1,120 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Code to give concrete types the methods which the user declared for
+//! them using `concrete_method!`. bindgen doesn't tell us about the member
+//! functions of class templates, so we can't discover these ourselves.
+//! Each becomes a C++ wrapper function which calls the method on its
+//! first argument, attached to the concrete type just as for maps.
+
+use autocxx_parser::IncludeCppConfig;
+use syn::{parse_quote, punctuated::Punctuated, token::Comma, FnArg, ReturnType, Type};
+
+use crate::{
+    conversion::{
+        api::{Api, ApiName, CppVisibility, FuncToConvert, Provenance},
+        apivec::ApiVec,
+    },
+    minisyn::minisynize_punctuated,
+    types::{make_ident, Namespace, QualifiedName},
+};
+
+use super::{
+    fun::function_wrapper::{CppFunctionBody, CppFunctionKind},
+    pod::PodPhase,
+    variadics::to_bindgen_type,
+};
+
+pub(crate) fn create_concrete_methods(
+    apis: ApiVec<PodPhase>,
+    config: &IncludeCppConfig,
+) -> ApiVec<PodPhase> {
+    let mut new_apis = ApiVec::new();
+    for api in apis.iter() {
+        if let Api::ConcreteType { name, .. } = api {
+            let name = &name.name;
+            for (method, receiver, types, output) in config.concrete_methods(name.get_final_item())
+            {
+                let self_ty = name.to_type_path();
+                let this: FnArg = match receiver.mutability {
+                    Some(_) => parse_quote! { this: *mut #self_ty },
+                    None => parse_quote! { this: *const #self_ty },
+                };
+                let mut inputs: Punctuated<FnArg, Comma> = Punctuated::new();
+                inputs.push(this);
+                for (idx, ty) in types.iter().enumerate() {
+                    let arg_name = make_ident(format!("arg{idx}"));
+                    let ty = to_cpp_method_type(ty);
+                    inputs.push(parse_quote! { #arg_name: #ty });
+                }
+                let output = match output {
+                    ReturnType::Type(arrow, ty) => {
+                        ReturnType::Type(*arrow, Box::new(to_cpp_method_type(ty)))
+                    }
+                    ReturnType::Default => ReturnType::Default,
+                };
+                new_apis.push(make_method(name, method, inputs, output));
+            }
+        }
+    }
+    let mut apis = apis;
+    apis.append(&mut new_apis);
+    apis
+}
+
+/// Users write references as Rust references, but the rest of our
+/// analysis expects them as bindgen would describe them.
+fn to_cpp_method_type(ty: &Type) -> Type {
+    match ty {
+        Type::Reference(tr) => {
+            let elem = to_cpp_method_type(&tr.elem);
+            match tr.mutability {
+                Some(_) => parse_quote! { __bindgen_marker_Reference < *mut #elem > },
+                None => parse_quote! { __bindgen_marker_Reference < *const #elem > },
+            }
+        }
+        _ => to_bindgen_type(ty),
+    }
+}
+
+fn make_method(
+    self_ty: &QualifiedName,
+    method_name: &str,
+    inputs: Punctuated<FnArg, Comma>,
+    output: ReturnType,
+) -> Api<PodPhase> {
+    let name = QualifiedName::new(
+        &Namespace::new(),
+        make_ident(format!("{}_{method_name}", self_ty.get_final_item())),
+    );
+    Api::Function {
+        fun: Box::new(FuncToConvert {
+            ident: make_ident(method_name),
+            doc_attrs: Vec::new(),
+            inputs: minisynize_punctuated(inputs),
+            output: output.into(),
+            vis: parse_quote! { pub },
+            virtualness: None,
+            cpp_vis: CppVisibility::Public,
+            special_member: None,
+            original_name: None,
+            self_ty: Some(self_ty.clone()),
+            synthesized_this_type: None,
+            synthetic_cpp: Some((
+                CppFunctionBody::MethodCall(make_ident(method_name)),
+                CppFunctionKind::Function,
+            )),
+            add_to_trait: None,
+            is_deleted: None,
+            provenance: Provenance::SynthesizedOther,
+            variadic: false,
+        }),
+        name: ApiName::new_from_qualified_name(name),
+        analysis: (),
+    }
+}
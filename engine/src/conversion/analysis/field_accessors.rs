@@ -0,0 +1,172 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Code to create accessors for the public fields of non-POD types listed
+//! in `field_accessors!`. Such types are opaque to Rust, so each field is
+//! reached through a C++ function returning a reference to it.
+
+use autocxx_parser::IncludeCppConfig;
+use syn::{
+    parse_quote, punctuated::Punctuated, token::Comma, Field, FnArg, ReturnType, Type, Visibility,
+};
+
+use crate::{
+    conversion::{
+        api::{Api, ApiName, CppVisibility, FuncToConvert, Provenance, TypeKind},
+        apivec::ApiVec,
+        type_helpers::{is_anonymous_union_member, type_is_reference},
+    },
+    minisyn::minisynize_punctuated,
+    types::{make_ident, QualifiedName},
+};
+
+use super::{
+    fun::function_wrapper::{CppFunctionBody, CppFunctionKind},
+    pod::{PodAnalysis, PodPhase},
+};
+
+pub(crate) fn create_field_accessors(
+    apis: ApiVec<PodPhase>,
+    config: &IncludeCppConfig,
+) -> ApiVec<PodPhase> {
+    let mut new_apis = ApiVec::new();
+    for api in apis.iter() {
+        if let Api::Struct {
+            name,
+            details,
+            analysis:
+                PodAnalysis {
+                    kind: TypeKind::NonPod,
+                    num_generics: 0usize,
+                    ..
+                },
+        } = api
+        {
+            // Only one member of a union is valid at a time, so we can't
+            // safely hand out references to any of them.
+            if details.is_union || !config.wants_field_accessors(&name.name.to_cpp_name()) {
+                continue;
+            }
+            for field in details.item.fields.iter().filter(|f| is_accessible(f)) {
+                new_apis.extend(create_accessors(&name.name, field).into_iter());
+            }
+        }
+    }
+    let mut apis = apis;
+    apis.append(&mut new_apis);
+    apis
+}
+
+/// Whether this is a public field which exists in the C++. bindgen adds
+/// fields for base classes, vtables and other things, all of which have
+/// names beginning with an underscore except for `vtable_`. We also can't
+/// have a reference to a reference, nor to a member of an anonymous union,
+/// for the same reason as for any other union.
+fn is_accessible(field: &Field) -> bool {
+    let is_public = matches!(field.vis, Visibility::Public(_));
+    match &field.ident {
+        Some(id) => {
+            let id = id.to_string();
+            is_public
+                && !id.starts_with('_')
+                && id != "vtable_"
+                && !may_be_renamed_by_bindgen(&id)
+                && !type_is_reference(&field.ty, false)
+                && !type_is_reference(&field.ty, true)
+                && !is_anonymous_union_member(field)
+        }
+        None => false,
+    }
+}
+
+/// The names which bindgen's `rust_mangle` renames by appending an
+/// underscore.
+const BINDGEN_RENAMED_KEYWORDS: &[&str] = &[
+    "abstract", "alignof", "as", "async", "await", "become", "box", "break", "const", "continue",
+    "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if",
+    "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "offsetof", "override",
+    "priv", "proc", "pub", "pure", "ref", "return", "Self", "self", "sizeof", "static", "struct",
+    "super", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield", "str", "bool", "f32", "f64", "usize", "isize", "u128", "i128",
+    "u64", "i64", "u32", "i32", "u16", "i16", "u8", "i8",
+];
+
+/// bindgen appends an underscore to fields named after Rust keywords, so
+/// a C++ field `type` appears as `type_`. bindgen doesn't tell us the
+/// original name, and a C++ field could equally be called `type_`, so we
+/// don't know what to call such fields in C++.
+fn may_be_renamed_by_bindgen(id: &str) -> bool {
+    id.strip_suffix('_')
+        .map(|stem| BINDGEN_RENAMED_KEYWORDS.contains(&stem))
+        .unwrap_or_default()
+}
+
+fn create_accessors(self_ty: &QualifiedName, field: &Field) -> [Api<PodPhase>; 2] {
+    let field_id = field.ident.as_ref().unwrap();
+    let field_ty = &field.ty;
+    let self_path = self_ty.to_type_path();
+    let getter_ty: Type = parse_quote! { __bindgen_marker_Reference < *const #field_ty > };
+    let mut_getter_ty: Type = parse_quote! { __bindgen_marker_Reference < *mut #field_ty > };
+    [
+        make_accessor(
+            self_ty,
+            field_id.to_string(),
+            parse_quote! { this: *const #self_path },
+            parse_quote! { -> #getter_ty },
+            field_id,
+        ),
+        make_accessor(
+            self_ty,
+            format!("{field_id}_mut"),
+            parse_quote! { this: *mut #self_path },
+            parse_quote! { -> #mut_getter_ty },
+            field_id,
+        ),
+    ]
+}
+
+fn make_accessor(
+    self_ty: &QualifiedName,
+    method_name: String,
+    inputs: Punctuated<FnArg, Comma>,
+    output: ReturnType,
+    field_id: &syn::Ident,
+) -> Api<PodPhase> {
+    let name = QualifiedName::new(
+        self_ty.get_namespace(),
+        make_ident(format!(
+            "{}_autocxx_field_{method_name}",
+            self_ty.get_final_item()
+        )),
+    );
+    Api::Function {
+        fun: Box::new(FuncToConvert {
+            ident: make_ident(method_name),
+            doc_attrs: Vec::new(),
+            inputs: minisynize_punctuated(inputs),
+            output: output.into(),
+            vis: parse_quote! { pub },
+            virtualness: None,
+            cpp_vis: CppVisibility::Public,
+            special_member: None,
+            original_name: None,
+            self_ty: Some(self_ty.clone()),
+            synthesized_this_type: None,
+            synthetic_cpp: Some((
+                CppFunctionBody::FieldAccess(field_id.clone().into()),
+                CppFunctionKind::Function,
+            )),
+            add_to_trait: None,
+            is_deleted: None,
+            provenance: Provenance::SynthesizedOther,
+            variadic: false,
+        }),
+        name: ApiName::new_from_qualified_name(name),
+        analysis: (),
+    }
+}
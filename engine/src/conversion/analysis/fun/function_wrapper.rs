@@ -28,6 +28,42 @@ pub(crate) enum CppConversionType {
     FromReturnValueToPlacementPtr,
     FromPointerToReference, // unwrapped_type is always Type::Ptr
     FromReferenceToPointer, // unwrapped_type is always Type::Ptr
+    /// unwrapped_type is always `&[u8]`, which we make into a `std::string_view`
+    FromSliceToByteView,
+    /// unwrapped_type is always `&[u8]`, which we make from any view of bytes
+    FromByteViewToSlice,
+    /// unwrapped_type is always a slice, from which we make a
+    /// `std::span`
+    FromSliceToSpan,
+    /// unwrapped_type is always a reference to a Rust array, which cxx
+    /// gives us as a `std::array`, from which we make a C array reference
+    FromStdArrayToCArray,
+    /// unwrapped_type is always Type::Ptr, which may be null
+    FromPtrToOptional,
+    /// unwrapped_type is always a `UniquePtr`, which may be null
+    FromUniquePtrToOptional,
+    /// unwrapped_type is always a `UniquePtr`, which will be null if the
+    /// optional was empty
+    FromOptionalToUniquePtr,
+    /// The function returns whether the optional had a value, and if so,
+    /// the value is put into the placement parameter. unwrapped_type is
+    /// the type of the value.
+    FromOptionalToPlacementPtr,
+    /// unwrapped_type is always a struct we've generated, for instance to
+    /// represent a `std::pair`, from which we make the C++ value
+    FromGeneratedStructToValue,
+    /// unwrapped_type is always a struct we've generated, for instance to
+    /// represent a `std::pair`, which we make from the C++ value
+    FromValueToGeneratedStruct,
+    /// unwrapped_type is always a `void*`, which we cast to a function
+    /// pointer of this type
+    FromVoidPtrToFunctionPointer(Box<crate::minisyn::Type>),
+    /// unwrapped_type is always a `void*`, to which we cast a function
+    /// pointer
+    FromFunctionPointerToVoidPtr,
+    /// unwrapped_type is always a `void*`, from which we make a
+    /// `std::function` with this signature which owns it
+    FromVoidPtrToStdFunction(Box<StdFunctionDetails>),
 }
 
 impl CppConversionType {
@@ -43,6 +79,14 @@ impl CppConversionType {
             CppConversionType::FromValueToUniquePtr => CppConversionType::FromUniquePtrToValue,
             CppConversionType::FromPointerToReference => CppConversionType::FromReferenceToPointer,
             CppConversionType::FromReferenceToPointer => CppConversionType::FromPointerToReference,
+            CppConversionType::FromSliceToByteView => CppConversionType::FromByteViewToSlice,
+            CppConversionType::FromByteViewToSlice => CppConversionType::FromSliceToByteView,
+            CppConversionType::FromGeneratedStructToValue => {
+                CppConversionType::FromValueToGeneratedStruct
+            }
+            CppConversionType::FromValueToGeneratedStruct => {
+                CppConversionType::FromGeneratedStructToValue
+            }
             _ => panic!("Did not expect to have to invert this conversion"),
         }
     }
@@ -61,6 +105,56 @@ pub(crate) enum RustConversionType {
     FromRValueParamToPtr,
     FromReferenceWrapperToPointer, // unwrapped_type is always Type::Ptr
     FromPointerToReferenceWrapper, // unwrapped_type is always Type::Ptr
+    FromAsRefBytes,                // unwrapped_type is always `&[u8]`
+    FromOptionToPtr,               // unwrapped_type is always Type::Ptr
+    FromOptionToUniquePtr,         // unwrapped_type is always a `UniquePtr`
+    FromUniquePtrToOption,         // unwrapped_type is always a `UniquePtr`
+    FromBoolToOption,              // unwrapped_type is the type of the value
+    FromOptionalOutParamToPtr,     // unwrapped_type is always Type::Ptr
+    FromPtrToOptionalRef,          // unwrapped_type is always Type::Ptr
+    FromPtrToStaticRef,            // unwrapped_type is always Type::Ptr
+    FromUniquePtrOutParamToPtr,    // unwrapped_type is always `*mut *mut T`
+    /// unwrapped_type is always a struct we've generated, and this is the
+    /// type which users see instead, such as a Rust tuple
+    FromValueToGeneratedStruct(crate::minisyn::Type),
+    /// unwrapped_type is always a struct we've generated, and this is the
+    /// type which users see instead, such as a Rust tuple
+    FromGeneratedStructToValue(crate::minisyn::Type),
+    /// unwrapped_type is always a `void*`, and this is the
+    /// `Option<unsafe extern "C" fn(...)>` which users see instead
+    FromFunctionPointerToVoidPtr(crate::minisyn::Type),
+    /// unwrapped_type is always a `void*`, and this is the
+    /// `Option<unsafe extern "C" fn(...)>` which users get instead
+    FromVoidPtrToFunctionPointer(crate::minisyn::Type),
+    /// unwrapped_type is always a `void*`. Users instead give us a closure,
+    /// and we pass a trampoline function which calls it.
+    FromClosureToTrampoline(Box<CallbackDetails>),
+    /// unwrapped_type is always a `void*`, which doesn't appear in the
+    /// wrapper function because we instead pass a pointer to the closure
+    /// given for the callback parameter of this name.
+    FromClosureToUserData(crate::minisyn::Ident),
+    /// unwrapped_type is always a `void*`. Users instead give us a closure,
+    /// which we box up along with functions to call and drop it.
+    FromClosureToStdFunction(Box<StdFunctionDetails>),
+}
+
+/// The signature of a C callback which users provide as a Rust closure.
+#[derive(Clone, Debug)]
+pub(crate) struct CallbackDetails {
+    /// The types of the parameters of the callback.
+    pub(crate) param_types: Vec<crate::minisyn::Type>,
+    /// Which of those parameters is the `void*` user data, in which
+    /// we'll pass a pointer to the closure.
+    pub(crate) user_data_idx: usize,
+    pub(crate) output: crate::minisyn::ReturnType,
+}
+
+/// The signature of a `std::function` which users provide as a Rust
+/// closure, as declared using `std_function!`.
+#[derive(Clone, Debug)]
+pub(crate) struct StdFunctionDetails {
+    pub(crate) param_types: Vec<crate::minisyn::Type>,
+    pub(crate) output: crate::minisyn::ReturnType,
 }
 
 impl RustConversionType {
@@ -167,6 +261,7 @@ impl TypeConversionPolicy {
     pub(crate) fn unconverted_rust_type(&self) -> Type {
         match self.cpp_conversion {
             CppConversionType::FromValueToUniquePtr => self.make_unique_ptr_type(),
+            CppConversionType::FromOptionalToPlacementPtr => parse_quote! { bool },
             _ => self.unwrapped_type.clone().into(),
         }
     }
@@ -214,6 +309,13 @@ impl TypeConversionPolicy {
                 | RustConversionType::FromPlacementParamToNewReturn
                 | RustConversionType::FromPointerToReferenceWrapper { .. }
                 | RustConversionType::FromReferenceWrapperToPointer { .. }
+                | RustConversionType::FromOptionToPtr
+                | RustConversionType::FromOptionalOutParamToPtr
+                | RustConversionType::FromUniquePtrOutParamToPtr
+                | RustConversionType::FromFunctionPointerToVoidPtr(_)
+                | RustConversionType::FromClosureToTrampoline(_)
+                | RustConversionType::FromClosureToUserData(_)
+                | RustConversionType::FromClosureToStdFunction(_)
         )
     }
 
@@ -232,6 +334,15 @@ impl TypeConversionPolicy {
     }
 }
 
+/// The C++ operator from which we find the ordering of two values.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum ComparisonOperator {
+    /// `operator<`, applied both ways round.
+    LessThan,
+    /// `operator<=>`.
+    ThreeWay,
+}
+
 #[derive(Clone, Debug)]
 pub(crate) enum CppFunctionBody {
     FunctionCall(Namespace, CppEffectiveName),
@@ -242,6 +353,44 @@ pub(crate) enum CppFunctionBody {
     Destructor(Namespace, Ident),
     AllocUninitialized(QualifiedName),
     FreeUninitialized(QualifiedName),
+    /// Compare the two arguments using the given operator, and reduce
+    /// the result to a small integer.
+    ThreeWayComparison(ComparisonOperator),
+    /// Stream the argument into a `std::ostringstream` and return the
+    /// resulting string.
+    StreamToString,
+    /// Hash the argument using `std::hash` for the given type.
+    StdHash(QualifiedName),
+    /// Make a new instance of the given iterator state type from the
+    /// `begin()` and `end()` of the argument.
+    IteratorBegin(QualifiedName),
+    /// Return a pointer to the current item of the iterator state, and
+    /// advance it, or return null if we've reached the end.
+    IteratorNext,
+    /// The number of entries in a map.
+    MapLen,
+    /// Return a pointer to the value for the given key in a map, or null
+    /// if there's no such key.
+    MapGet,
+    /// Whether a map contains the given key.
+    MapContainsKey,
+    /// Insert a key and value into a map, replacing any existing value,
+    /// and return whether the key is new.
+    MapInsert,
+    /// Remove a key from a map, and return whether it was there.
+    MapRemove,
+    /// The key of an entry in a map.
+    MapEntryKey,
+    /// The value of an entry in a map.
+    MapEntryValue,
+    /// The given field of the argument.
+    FieldAccess(Ident),
+    /// Call the given method on the first argument, passing the rest.
+    MethodCall(Ident),
+    /// The value of the given global variable or static data member.
+    StaticData(QualifiedName),
+    /// A pointer to the given global variable or static data member.
+    StaticDataAddress(QualifiedName),
 }
 
 #[derive(Clone, Debug)]
@@ -206,9 +206,9 @@ pub(super) fn find_constructors_present(
                 PodAnalysis {
                     // Do not include TypeKind::Opaque here
                     kind:
-                        crate::conversion::api::TypeKind::Abstract
+                        struct_kind @ (crate::conversion::api::TypeKind::Abstract
                         | crate::conversion::api::TypeKind::Pod
-                        | crate::conversion::api::TypeKind::NonPod,
+                        | crate::conversion::api::TypeKind::NonPod),
                     bases,
                     field_info,
                     num_generics: 0usize,
@@ -256,12 +256,21 @@ pub(super) fn find_constructors_present(
                         },
                         _ => None,
                     },
+                    // Only found in function signatures, never in fields.
+                    TypeKind::Optional
+                    | TypeKind::Span
+                    | TypeKind::Tuple(_)
+                    | TypeKind::Variant(_)
+                    | TypeKind::FunctionPointer(_) => None,
+                    // Only found in function signatures, never in fields.
+                    TypeKind::ByteView => None,
                     // TODO: https://github.com/google/autocxx/issues/865 Figure out how to
                     // differentiate between pointers and references coming from C++. Pointers
                     // have a default constructor.
                     TypeKind::Pointer
                     | TypeKind::Reference
                     | TypeKind::MutableReference
+                    | TypeKind::ArrayReference
                     | TypeKind::RValueReference => Some(ItemsFound {
                         default_constructor: SpecialMemberFound::NotPresent,
                         destructor: SpecialMemberFound::Implicit,
@@ -286,9 +295,14 @@ pub(super) fn find_constructors_present(
             // We need to extend our knowledge to understand the constructor behavior of things in
             // known_types.rs, then we'll be able to cope with types which contain strings,
             // unique_ptrs etc.
+            //
+            // A union's implicit special members are deleted if any of its
+            // members has a non-trivial one, so we're similarly conservative
+            // about any union which isn't POD.
             let items_found = if bases_items_found.len() != bases.len()
                 || fields_items_found.len() != field_info.len()
                 || unknown_types.contains(&name.name)
+                || (details.is_union && *struct_kind != crate::conversion::api::TypeKind::Pod)
             {
                 let is_explicit = |kind: ExplicitKind| -> SpecialMemberFound {
                     // TODO: For https://github.com/google/autocxx/issues/815, map
@@ -15,17 +15,25 @@ mod subclass;
 use crate::{
     conversion::{
         analysis::{
-            fun::function_wrapper::{CppConversionType, CppFunctionKind},
-            type_converter::{self, add_analysis, TypeConversionContext, TypeConverter},
+            fun::function_wrapper::{
+                CallbackDetails, ComparisonOperator, CppConversionType, CppFunctionKind,
+                StdFunctionDetails,
+            },
+            type_converter::{
+                self, add_analysis, TypeConversionContext, TypeConverter, VariantDetails,
+            },
         },
         api::{
             ApiName, CastMutability, FuncToConvert, NullPhase, Provenance, SubclassName,
-            TraitImplSignature, TraitSynthesis, UnsafetyNeeded,
+            TraitImplSignature, TraitSynthesis, UnsafetyNeeded, VariantAlternative,
         },
         apivec::ApiVec,
         convert_error::{ConvertErrorWithContext, ErrorContext, ErrorContextType},
         error_reporter::{convert_apis, report_any_error},
-        type_helpers::{type_is_reference, unwrap_has_opaque},
+        type_helpers::{
+            extract_option_type, extract_pinned_mutable_reference_type, extract_slice_type,
+            type_is_reference, unwrap_has_opaque, unwrap_reference,
+        },
         CppEffectiveName, CppOriginalName,
     },
     known_types::known_types,
@@ -41,10 +49,10 @@ use autocxx_parser::{ExternCppType, IncludeCppConfig, UnsafePolicy};
 use function_wrapper::{CppFunction, CppFunctionBody, TypeConversionPolicy};
 use itertools::Itertools;
 use proc_macro2::Span;
-use quote::quote;
+use quote::{quote, ToTokens};
 use syn::{
     parse_quote, punctuated::Punctuated, token::Comma, Ident, Pat, PatType, ReturnType, Type,
-    TypePath, TypePtr, TypeReference, Visibility,
+    TypeArray, TypeBareFn, TypePath, TypePtr, TypeReference, Visibility,
 };
 
 use crate::{
@@ -52,7 +60,7 @@ use crate::{
         api::{AnalysisPhase, Api, TypeKind},
         ConvertErrorFromCpp,
     },
-    types::{make_ident, validate_ident_ok_for_cxx, Namespace, QualifiedName},
+    types::{make_ident, validate_ident_ok_for_cxx, CppOperator, Namespace, QualifiedName},
 };
 
 use self::{
@@ -72,6 +80,7 @@ use super::{
     pod::{PodAnalysis, PodPhase},
     tdef::TypedefAnalysis,
     type_converter::Annotated,
+    variadics::to_bindgen_type,
 };
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
@@ -97,6 +106,22 @@ pub(crate) enum TraitMethodKind {
     Destructor,
     Alloc,
     Dealloc,
+    Operator,
+    Hash,
+    Iterator,
+}
+
+/// How a trait method takes `self`, where that's dictated by the
+/// trait rather than by the underlying C++ function.
+#[derive(Clone, Debug)]
+pub(crate) enum TraitReceiver {
+    /// `self`, where the trait is implemented for the type of the first
+    /// parameter.
+    Value,
+    /// `&self`
+    Reference,
+    /// `&mut self`, where the trait is implemented for `Pin<&mut T>`.
+    PinnedMutableReference,
 }
 
 #[derive(Clone, Debug)]
@@ -111,6 +136,40 @@ pub(crate) struct TraitMethodDetails {
     /// The function we're calling from the trait requires unsafe even
     /// though the trait and its function aren't.
     pub(crate) trait_call_is_unsafe: bool,
+    /// If set, the first parameter becomes `self` in this form.
+    pub(crate) receiver: Option<TraitReceiver>,
+    /// What we do with the C++ return value.
+    pub(crate) output: TraitMethodOutput,
+}
+
+/// What a trait method does with the value returned from C++.
+#[derive(Clone, Debug)]
+pub(crate) enum TraitMethodOutput {
+    /// Returned unchanged.
+    Unchanged,
+    /// Returned unchanged, and also used as the trait's associated `Output`
+    /// type (or, if it's a reference, the type referred to).
+    AssociatedType,
+    /// Thrown away.
+    Discarded,
+    /// The C++ wrapper reduces a comparison to -1, 0 or 1 (or 2 if the
+    /// operands are unordered) which becomes an `Option<Ordering>`.
+    /// If the ordering is total, we also implement `Ord` and `Eq`.
+    Ordering {
+        total: bool,
+        operator: ComparisonOperator,
+    },
+    /// The C++ returns a string which is written to a `Formatter`.
+    /// We implement `Debug` in the same way.
+    Formatted,
+    /// The C++ returns a hash value which is fed to a `Hasher`.
+    Hashed,
+    /// The C++ returns the state needed to iterate over `self`, which
+    /// we wrap in an `autocxx::CppIterator` (or `autocxx::CppIteratorMut`).
+    IntoIterator { state: QualifiedName, mutable: bool },
+    /// Returned unchanged, and the type pointed to is also used as the
+    /// trait's associated `Item` type.
+    IteratorItem,
 }
 
 #[derive(Clone, Debug)]
@@ -177,6 +236,9 @@ pub(crate) struct FnAnalysis {
     pub(crate) externally_callable: bool,
     /// Whether we need to generate a Rust-side calling function
     pub(crate) rust_wrapper_needed: bool,
+    /// Whether C++ exceptions thrown by this function should be
+    /// reported to Rust as a `Result`.
+    pub(crate) throws: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -296,7 +358,10 @@ pub(crate) struct FnAnalyzer<'a> {
     subclasses_by_superclass: HashMap<QualifiedName, Vec<SubclassName>>,
     nested_type_name_map: HashMap<QualifiedName, String>,
     generic_types: HashSet<QualifiedName>,
+    concrete_types: HashSet<QualifiedName>,
+    variant_structs: HashSet<QualifiedName>,
     types_in_anonymous_namespace: HashSet<QualifiedName>,
+    three_way_comparable_types: HashSet<QualifiedName>,
     existing_superclass_trait_api_names: HashSet<QualifiedName>,
     force_wrapper_generation: bool,
 }
@@ -320,8 +385,11 @@ impl<'a> FnAnalyzer<'a> {
             subclasses_by_superclass: subclass::subclasses_by_superclass(&apis),
             nested_type_name_map: Self::build_nested_type_map(&apis),
             generic_types: Self::build_generic_type_set(&apis),
+            concrete_types: Self::build_concrete_type_set(&apis),
+            variant_structs: HashSet::new(),
             existing_superclass_trait_api_names: HashSet::new(),
             types_in_anonymous_namespace: Self::build_types_in_anonymous_namespace(&apis),
+            three_way_comparable_types: Self::build_three_way_comparable_type_set(&apis),
             force_wrapper_generation,
         };
         let mut results = ApiVec::new();
@@ -402,6 +470,13 @@ impl<'a> FnAnalyzer<'a> {
             .collect()
     }
 
+    fn build_concrete_type_set(apis: &ApiVec<PodPhase>) -> HashSet<QualifiedName> {
+        apis.iter()
+            .filter(|api| matches!(api, Api::ConcreteType { .. }))
+            .map(|api| api.name().clone())
+            .collect()
+    }
+
     fn build_types_in_anonymous_namespace(apis: &ApiVec<PodPhase>) -> HashSet<QualifiedName> {
         apis.iter()
             .filter_map(|api| match api {
@@ -418,6 +493,26 @@ impl<'a> FnAnalyzer<'a> {
             .collect()
     }
 
+    /// Types with an `operator<=>`, which we use for their ordering in
+    /// preference to any `operator<`.
+    fn build_three_way_comparable_type_set(apis: &ApiVec<PodPhase>) -> HashSet<QualifiedName> {
+        apis.iter()
+            .filter_map(|api| match api {
+                Api::Function { name, fun, .. }
+                    if matches!(
+                        fun.add_to_trait,
+                        Some(TraitSynthesis::Operator(CppOperator::Cmp))
+                    ) =>
+                {
+                    fun.self_ty
+                        .clone()
+                        .or_else(|| first_operand_type_name(fun, name.name.get_namespace()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     /// Builds a mapping from a qualified type name to the last 'nest'
     /// of its name, if it has multiple elements.
     fn build_nested_type_map(apis: &ApiVec<PodPhase>) -> HashMap<QualifiedName, String> {
@@ -673,6 +768,10 @@ impl<'a> FnAnalyzer<'a> {
             }
         }
 
+        // Consider whether we need to synthesize versions of this function
+        // which omit defaulted parameters.
+        self.add_default_param_variants(&name, &fun, &analysis, &mut results);
+
         results.push(Api::Function {
             fun,
             analysis,
@@ -682,6 +781,115 @@ impl<'a> FnAnalyzer<'a> {
         Ok(Box::new(results.into_iter()))
     }
 
+    /// If the user has told us (via `default_params!`) that some trailing
+    /// parameters of this function have default values, synthesize extra
+    /// versions of the function which omit them. Each calls the original
+    /// C++ function with fewer arguments, so the C++ compiler supplies
+    /// the defaults.
+    ///
+    /// The full version retains its normal name. The others are named
+    /// for the final parameter which they accept, e.g. `foo_with_b`, or
+    /// `foo_with_defaults` if they accept none.
+    fn add_default_param_variants(
+        &mut self,
+        name: &ApiName,
+        fun: &FuncToConvert,
+        analysis: &FnAnalysis,
+        results: &mut ApiVec<FnPrePhase1>,
+    ) {
+        if analysis.ignore_reason.is_err() {
+            return;
+        }
+        let num_defaults = match self
+            .config
+            .num_default_params(&name_for_directives(name, &analysis.kind))
+        {
+            Some(num_defaults) => num_defaults,
+            None => return,
+        };
+        let ns = name.name.get_namespace();
+        let cpp_name = name.cpp_name();
+        let (synthetic_cpp, first_param, type_ident) = match &analysis.kind {
+            FnKind::Function => (
+                (
+                    CppFunctionBody::FunctionCall(ns.clone(), cpp_name),
+                    CppFunctionKind::Function,
+                ),
+                0,
+                None,
+            ),
+            FnKind::Method {
+                impl_for,
+                method_kind,
+            } => {
+                let (synthetic_cpp, first_param) = match method_kind {
+                    MethodKind::Constructor { .. } => (
+                        (
+                            CppFunctionBody::PlacementNew(ns.clone(), impl_for.get_final_ident()),
+                            CppFunctionKind::Constructor,
+                        ),
+                        1,
+                    ),
+                    MethodKind::Static => (
+                        (
+                            CppFunctionBody::StaticMethodCall(
+                                ns.clone(),
+                                impl_for.get_final_ident(),
+                                cpp_name,
+                            ),
+                            CppFunctionKind::Function,
+                        ),
+                        0,
+                    ),
+                    _ => (
+                        (
+                            CppFunctionBody::FunctionCall(ns.clone(), cpp_name),
+                            CppFunctionKind::Method,
+                        ),
+                        1,
+                    ),
+                };
+                (
+                    synthetic_cpp,
+                    first_param,
+                    Some(impl_for.get_final_item().to_string()),
+                )
+            }
+            FnKind::TraitMethod { .. } => return,
+        };
+        let num_params = fun.inputs.len();
+        let num_defaults = num_defaults.min(num_params.saturating_sub(first_param));
+        for num_supplied in (num_params - num_defaults..num_params).rev() {
+            let suffix = if num_supplied > first_param {
+                describe_arg(&fun.inputs[num_supplied - 1])
+            } else {
+                "defaults".into()
+            };
+            let ideal_rust_name = format!("{}_with_{}", analysis.rust_name, suffix);
+            let rust_name = match &type_ident {
+                Some(type_ident) => self.get_overload_name(ns, type_ident, ideal_rust_name),
+                None => self.get_function_overload_name(ns, ideal_rust_name),
+            };
+            let new_func = Box::new(FuncToConvert {
+                provenance: Provenance::SynthesizedOther,
+                inputs: fun.inputs.iter().take(num_supplied).cloned().collect(),
+                synthetic_cpp: Some(synthetic_cpp.clone()),
+                ..fun.clone()
+            });
+            let (new_analysis, new_name) = self.analyze_foreign_fn(
+                name.clone(),
+                &new_func,
+                TypeConversionSophistication::Regular,
+                Some(rust_name),
+            );
+            results.push(Api::Function {
+                fun: new_func,
+                analysis: new_analysis,
+                name: new_name,
+            });
+        }
+    }
+
     /// Adds an API, usually a synthesized API. Returns the final calculated API name, which can be used
     /// for others to depend on this.
     fn analyze_and_add<P: AnalysisPhase<FunAnalysis = FnAnalysis>>(
@@ -737,23 +945,50 @@ impl<'a> FnAnalyzer<'a> {
             .unwrap_or(&initial_rust_name);
         let diagnostic_name = QualifiedName::new(ns, make_ident(diagnostic_name));
 
+        // The user may have told us things about parameters which bindgen
+        // can't, such as the signatures of `std::function`s. We don't yet
+        // know for sure whether this is a method, but bindgen has told us
+        // the type of any receiver.
+        let directive_name_for_params = name_for_directives_in(&name, fun.self_ty.as_ref());
+
         // Now let's analyze all the parameters.
         // See if any have annotations which our fork of bindgen has craftily inserted...
         let (param_details, bads): (Vec<_>, Vec<_>) = fun
             .inputs
             .iter()
             .map(|i| {
-                self.convert_fn_arg(
-                    i,
-                    ns,
-                    &diagnostic_name,
-                    &fun.synthesized_this_type,
-                    true,
-                    false,
-                    None,
-                    sophistication,
-                    false,
-                )
+                let (std_function_signature, std_array, mutable_span) = match &i.0 {
+                    syn::FnArg::Typed(pt) => {
+                        let param_name = pt.pat.to_token_stream().to_string();
+                        (
+                            self.config
+                                .std_function_signature(&directive_name_for_params, &param_name),
+                            self.config
+                                .std_array_type(&directive_name_for_params, &param_name),
+                            self.config
+                                .is_mutable_span(&directive_name_for_params, &param_name),
+                        )
+                    }
+                    _ => (None, None, false),
+                };
+                match (std_function_signature, std_array) {
+                    (Some((param_types, output)), _) => {
+                        self.convert_std_function_arg(i, ns, param_types, output, sophistication)
+                    }
+                    (None, Some(array)) => self.convert_std_array_arg(i, ns, array),
+                    (None, None) => self.convert_fn_arg(
+                        i,
+                        ns,
+                        &diagnostic_name,
+                        &fun.synthesized_this_type,
+                        true,
+                        false,
+                        None,
+                        sophistication,
+                        false,
+                        mutable_span,
+                    ),
+                }
                 .map_err(|err| ConvertErrorFromCpp::Argument {
                     arg: describe_arg(i),
                     err: Box::new(err),
@@ -817,10 +1052,11 @@ impl<'a> FnAnalyzer<'a> {
         // Part two, work out if this is a function, or method, or whatever.
         // First determine if this is actually a trait implementation.
         let trait_details = self.trait_creation_details_for_synthetic_function(
-            &fun.add_to_trait,
+            fun,
             ns,
             &ideal_rust_name,
             &self_ty,
+            &param_details,
         );
         let (kind, error_context, rust_name) = if let Some(trait_details) = trait_details {
             trait_details
@@ -895,6 +1131,8 @@ impl<'a> FnAnalyzer<'a> {
                                 method_name: make_ident(method_name),
                                 parameter_reordering: Some(vec![1, 0]),
                                 trait_call_is_unsafe: false,
+                                receiver: None,
+                                output: TraitMethodOutput::Unchanged,
                             }),
                         },
                         error_context,
@@ -931,6 +1169,8 @@ impl<'a> FnAnalyzer<'a> {
                             method_name: make_ident("drop"),
                             parameter_reordering: None,
                             trait_call_is_unsafe: false,
+                            receiver: None,
+                            output: TraitMethodOutput::Unchanged,
                         }),
                     },
                     error_context,
@@ -987,7 +1227,8 @@ impl<'a> FnAnalyzer<'a> {
         } else {
             // Not a method.
             // What shall we call this function? It may be overloaded.
-            let rust_name = self.get_function_overload_name(ns, ideal_rust_name);
+            let rust_name = predetermined_rust_name
+                .unwrap_or_else(|| self.get_function_overload_name(ns, ideal_rust_name));
             (
                 FnKind::Function,
                 ErrorContext::new_for_item(make_ident(&rust_name)),
@@ -1140,8 +1381,6 @@ impl<'a> FnAnalyzer<'a> {
             // treat it as an assignment operator, but anything below we still consider when
             // deciding which other C++ special member functions are implicitly defined.
             set_ignore_reason(ConvertErrorFromCpp::AssignmentOperator)
-        } else if return_type_is_reference(&fun.output) {
-            set_ignore_reason(ConvertErrorFromCpp::RValueReturn)
         } else if matches!(fun.is_deleted, Some(Explicitness::Deleted)) {
             set_ignore_reason(ConvertErrorFromCpp::Deleted)
         } else {
@@ -1157,13 +1396,27 @@ impl<'a> FnAnalyzer<'a> {
                 } if !known_types().is_cxx_acceptable_receiver(impl_for) => {
                     set_ignore_reason(ConvertErrorFromCpp::UnsupportedReceiver);
                 }
-                FnKind::Method { ref impl_for, .. } if !self.is_on_allowlist(impl_for) => {
+                FnKind::Method { ref impl_for, .. }
+                    if !self.is_on_allowlist(impl_for)
+                        && !self.concrete_types.contains(impl_for) =>
+                {
                     // Bindgen will output methods for types which have been encountered
                     // virally as arguments on other allowlisted types. But we don't want
                     // to generate methods unless the user has specifically asked us to.
-                    // It may, for instance, be a private type.
+                    // It may, for instance, be a private type. Concrete template types
+                    // only have the methods we make for them, so those are fine.
                     set_ignore_reason(ConvertErrorFromCpp::MethodOfNonAllowlistedType);
                 }
+                // A free operator whose first operand is a primitive (or
+                // another type we don't generate) would need its trait
+                // implemented for that type, which isn't ours.
+                FnKind::TraitMethod {
+                    kind: TraitMethodKind::Operator,
+                    ref impl_for,
+                    ..
+                } if known_types().is_known_type(impl_for) => {
+                    set_ignore_reason(ConvertErrorFromCpp::OperatorOnBuiltInType);
+                }
                 FnKind::Method { ref impl_for, .. } | FnKind::TraitMethod { ref impl_for, .. } => {
                     if self.is_generic_type(impl_for) {
                         set_ignore_reason(ConvertErrorFromCpp::MethodOfGenericType);
@@ -1196,22 +1449,190 @@ impl<'a> FnAnalyzer<'a> {
             }
             None => None,
         };
-        let underlying_cpp_function_name = cpp_original_name
-            .cloned()
-            .map(|n| n.to_effective_name())
-            .unwrap_or_else(|| CppEffectiveName::from_rust_name(rust_name.clone()));
+        // bindgen knows operators by a different name from C++.
+        let cpp_operator = match fun.add_to_trait {
+            Some(TraitSynthesis::Operator(op)) => Some(op),
+            _ => None,
+        };
+        let underlying_cpp_function_name = match cpp_operator {
+            Some(op) => CppEffectiveName::from_cpp_operator(op),
+            None => cpp_original_name
+                .cloned()
+                .map(|n| n.to_effective_name())
+                .unwrap_or_else(|| CppEffectiveName::from_rust_name(rust_name.clone())),
+        };
         let mut cxxbridge_name = make_ident(&cxxbridge_name);
 
+        // The user may have told us that this function throws exceptions.
+        // We can only report those to Rust from ordinary functions and methods:
+        // for constructors and the like, the shape of the Rust API is
+        // dictated by the traits we implement.
+        let directive_name = name_for_directives(&name, &kind);
+        let throws = self.config.is_throwing_function(&directive_name);
+        if throws
+            && !matches!(
+                kind,
+                FnKind::Function
+                    | FnKind::Method {
+                        method_kind: MethodKind::Normal
+                            | MethodKind::Static
+                            | MethodKind::Virtual(_)
+                            | MethodKind::PureVirtual(_),
+                        ..
+                    }
+            )
+        {
+            set_ignore_reason(ConvertErrorFromCpp::ThrowingSpecialMember);
+        }
+
+        // The user may also have told us that some parameters are out-params,
+        // which we return instead of taking. Virtual methods are excluded
+        // because Rust subclasses would need to do the reverse.
+        let mut has_out_params = false;
+        for pd in param_details.iter_mut() {
+            let param_name = pd.name.to_token_stream().to_string();
+            if !self.config.is_out_param(&directive_name, &param_name) {
+                continue;
+            }
+            let kind_ok = matches!(
+                kind,
+                FnKind::Function
+                    | FnKind::Method {
+                        method_kind: MethodKind::Normal | MethodKind::Static,
+                        ..
+                    }
+            );
+            if kind_ok && self.is_unique_ptr_out_param(pd.conversion.cxxbridge_type()) {
+                // We create the pointer ourselves, so only the bridge
+                // function need be unsafe.
+                pd.conversion.rust_conversion = RustConversionType::FromUniquePtrOutParamToPtr;
+                pd.requires_unsafe = UnsafetyNeeded::JustBridge;
+                has_out_params = true;
+            } else {
+                set_ignore_reason(ConvertErrorFromCpp::InvalidOutParam(param_name));
+            }
+        }
+
+        // Likewise, the user may have paired a function pointer with the
+        // `void*` which the C++ passes back to it, in which case we can
+        // take a Rust closure instead. We keep the closure on the stack,
+        // so we mustn't return from our wrapper until the C++ is done
+        // with it, which rules out returning an `impl New`. Nothing stops
+        // the C++ keeping the callback to call later, so just as for any
+        // other function pointer, the function is `unsafe`.
+        let callbacks: Vec<_> = param_details
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, pd)| {
+                let param_name = pd.name.to_token_stream().to_string();
+                self.config
+                    .callback_user_data(&directive_name, &param_name)
+                    .map(|user_data| (idx, param_name, user_data.to_string()))
+            })
+            .collect();
+        let has_callbacks = !callbacks.is_empty();
+        for (callback_idx, callback_name, user_data_name) in callbacks {
+            let user_data_idx = param_details
+                .iter()
+                .position(|pd| pd.name.to_token_stream().to_string() == user_data_name)
+                .filter(|idx| is_void_ptr(param_details[*idx].conversion.cxxbridge_type()));
+            let details = match (
+                &param_details[callback_idx].conversion.rust_conversion,
+                user_data_idx,
+            ) {
+                (RustConversionType::FromFunctionPointerToVoidPtr(user_ty), Some(_)) => {
+                    callback_details(user_ty)
+                }
+                _ => None,
+            };
+            match (details, user_data_idx) {
+                (Some(details), Some(user_data_idx)) => {
+                    let callback = &mut param_details[callback_idx];
+                    callback.conversion.rust_conversion =
+                        RustConversionType::FromClosureToTrampoline(Box::new(details));
+                    callback.requires_unsafe = UnsafetyNeeded::Always;
+                    let user_data = &mut param_details[user_data_idx];
+                    user_data.conversion.rust_conversion =
+                        RustConversionType::FromClosureToUserData(make_ident(&callback_name));
+                    user_data.requires_unsafe = UnsafetyNeeded::JustBridge;
+                }
+                _ => set_ignore_reason(ConvertErrorFromCpp::InvalidCallback(callback_name)),
+            }
+        }
+
+        // Operator traits need an `Output` type we can name, rather than
+        // an `impl New`. Some of them discard the C++ return value entirely
+        // (e.g. `operator+=`'s reference to `*this`), and comparisons
+        // return whatever our wrapper makes of them.
+        let (output, avoid_placement_return) = match kind {
+            FnKind::TraitMethod {
+                kind: TraitMethodKind::Operator,
+                ref details,
+                ..
+            } => (
+                match details.output {
+                    TraitMethodOutput::Discarded => ReturnType::Default.into(),
+                    TraitMethodOutput::Ordering { .. } => {
+                        let output: ReturnType = parse_quote! { -> i8 };
+                        output.into()
+                    }
+                    _ => fun.output.clone(),
+                },
+                true,
+            ),
+            _ => (
+                fun.output.clone(),
+                throws || has_out_params || has_callbacks,
+            ),
+        };
+        // A returned rvalue reference is an object we're allowed to move
+        // from, so we treat it just like a return by value: our wrapper
+        // function move-constructs a new object from it.
+        let rvalue_return = unwrap_rvalue_reference_return(&output);
+        let returns_rvalue_reference = rvalue_return.is_some();
+        let output = rvalue_return.unwrap_or(output);
+
         // Analyze the return type, just as we previously did for the
         // parameters.
-        let mut return_analysis = self
-            .convert_return_type(&fun.output, ns, &diagnostic_name, sophistication)
-            .unwrap_or_else(|err| {
-                set_ignore_reason(err);
-                ReturnTypeAnalysis::default()
-            });
+        let mut return_analysis = match self
+            .config
+            .std_array_type(&directive_name_for_params, "return")
+        {
+            Some(array) => self.convert_std_array_return(&output, ns, array),
+            None => self.convert_return_type(
+                &output,
+                ns,
+                &diagnostic_name,
+                sophistication,
+                avoid_placement_return,
+            ),
+        }
+        .unwrap_or_else(|err| {
+            set_ignore_reason(err);
+            ReturnTypeAnalysis::default()
+        });
+        // Looking up a key in a map gives us null if it's not there.
+        if matches!(fun.synthetic_cpp, Some((CppFunctionBody::MapGet, _))) {
+            if let Some(conversion) = return_analysis.conversion.as_mut() {
+                conversion.rust_conversion = RustConversionType::FromPtrToOptionalRef;
+            }
+        }
+        // Static data lives forever.
+        let is_static_data_address = matches!(
+            fun.synthetic_cpp,
+            Some((CppFunctionBody::StaticDataAddress(_), _))
+        );
+        if is_static_data_address {
+            if let Some(conversion) = return_analysis.conversion.as_mut() {
+                conversion.rust_conversion = RustConversionType::FromPtrToStaticRef;
+            }
+        }
         let mut deps = params_deps;
         deps.extend(return_analysis.deps.drain(..));
+        // Iteration state is no use unless we can step through it.
+        if let Some(TraitSynthesis::IntoIterator { next_fn, .. }) = &fun.add_to_trait {
+            deps.insert(next_fn.clone());
+        }
 
         // Sometimes, the return type will actually be a value type
         // for which we instead want to _pass_ a pointer into which the value
@@ -1221,7 +1642,15 @@ impl<'a> FnAnalyzer<'a> {
             params.push(extra_param);
         }
 
-        let requires_unsafe = self.should_be_unsafe(&param_details, &kind);
+        let returns_mut_ptr = matches!(&fun.output.0, ReturnType::Type(_, ty)
+            if matches!(ty.as_ref(), Type::Ptr(TypePtr { mutability: Some(_), .. })));
+        // Nothing stops two callers having mutable references to the
+        // same static data at once.
+        let requires_unsafe = if is_static_data_address && returns_mut_ptr {
+            UnsafetyNeeded::Always
+        } else {
+            self.should_be_unsafe(&param_details, &kind)
+        };
 
         // The following sections reject some types of function because of the arrangement
         // of Rust references. We could lift these restrictions when/if we switch to using
@@ -1305,6 +1734,8 @@ impl<'a> FnAnalyzer<'a> {
             _ if param_conversion_needed => true,
             _ if ret_type_conversion_needed => true,
             _ if cpp_name_incompatible_with_cxx => true,
+            _ if cpp_operator.is_some() => true,
+            _ if returns_rvalue_reference => true,
             _ if fun.synthetic_cpp.is_some() => true,
             _ if self.force_wrapper_generation => true,
             _ => false,
@@ -1322,7 +1753,27 @@ impl<'a> FnAnalyzer<'a> {
                 self.config
                     .uniquify_name_per_mod(&format!("{cxxbridge_name}{joiner}autocxx_wrapper")),
             );
-            let (payload, cpp_function_kind) = match fun.synthetic_cpp.as_ref().cloned() {
+            // Comparison operators are reduced to a three-way comparison.
+            let ordering_operator = match &kind {
+                FnKind::TraitMethod {
+                    kind: TraitMethodKind::Operator,
+                    details,
+                    ..
+                } => match details.output {
+                    TraitMethodOutput::Ordering { operator, .. } => Some(operator),
+                    _ => None,
+                },
+                _ => None,
+            };
+            let synthetic_cpp = fun.synthetic_cpp.clone().or_else(|| {
+                ordering_operator.map(|operator| {
+                    (
+                        CppFunctionBody::ThreeWayComparison(operator),
+                        CppFunctionKind::Function,
+                    )
+                })
+            });
+            let (payload, cpp_function_kind) = match synthetic_cpp {
                 Some((payload, cpp_function_kind)) => (payload, cpp_function_kind),
                 None => match kind {
                     FnKind::Method {
@@ -1362,6 +1813,18 @@ impl<'a> FnAnalyzer<'a> {
                         CppFunctionBody::FunctionCall(ns.clone(), underlying_cpp_function_name),
                         CppFunctionKind::Method,
                     ),
+                    FnKind::TraitMethod {
+                        kind: TraitMethodKind::Operator,
+                        ..
+                    } if param_details
+                        .first()
+                        .is_some_and(|pd| pd.self_type.is_some()) =>
+                    {
+                        (
+                            CppFunctionBody::FunctionCall(ns.clone(), underlying_cpp_function_name),
+                            CppFunctionKind::Method,
+                        )
+                    }
                     _ => (
                         CppFunctionBody::FunctionCall(ns.clone(), underlying_cpp_function_name),
                         CppFunctionKind::Function,
@@ -1456,6 +1919,7 @@ impl<'a> FnAnalyzer<'a> {
             ignore_reason,
             externally_callable,
             rust_wrapper_needed,
+            throws,
         };
         // For everything other than functions, the API name is immutable.
         // It would be nice to get to that point with functions, but at present
@@ -1504,6 +1968,7 @@ impl<'a> FnAnalyzer<'a> {
             force_rust_conversion,
             sophistication,
             construct_into_self,
+            false,
         )
         .map(|(new_arg, new_analysis)| {
             param_details[param_idx] = new_analysis;
@@ -1530,103 +1995,424 @@ impl<'a> FnAnalyzer<'a> {
     /// of a trait, rather than a function/method.
     fn trait_creation_details_for_synthetic_function(
         &mut self,
-        synthesis: &Option<TraitSynthesis>,
+        fun: &FuncToConvert,
         ns: &Namespace,
         ideal_rust_name: &str,
         self_ty: &Option<QualifiedName>,
+        param_details: &[ArgumentAnalysis],
     ) -> Option<(FnKind, ErrorContext, String)> {
-        synthesis.as_ref().and_then(|synthesis| match synthesis {
-            TraitSynthesis::Cast { to_type, mutable } => {
-                let rust_name = self.get_function_overload_name(ns, ideal_rust_name.to_string());
-                let from_type = self_ty.as_ref().unwrap();
-                let from_type_path = from_type.to_type_path();
-                let to_type = to_type.to_type_path();
-                let (trait_signature, ty, method_name) = match *mutable {
-                    CastMutability::ConstToConst => (
-                        parse_quote! {
-                            AsRef < #to_type >
-                        },
-                        Type::Path(from_type_path),
-                        "as_ref",
-                    ),
-                    CastMutability::MutToConst => (
-                        parse_quote! {
-                            AsRef < #to_type >
+        fun.add_to_trait
+            .as_ref()
+            .and_then(|synthesis| match synthesis {
+                TraitSynthesis::Cast { to_type, mutable } => {
+                    let rust_name =
+                        self.get_function_overload_name(ns, ideal_rust_name.to_string());
+                    let from_type = self_ty.as_ref().unwrap();
+                    let from_type_path = from_type.to_type_path();
+                    let to_type = to_type.to_type_path();
+                    let (trait_signature, ty, method_name) = match *mutable {
+                        CastMutability::ConstToConst => (
+                            parse_quote! {
+                                AsRef < #to_type >
+                            },
+                            Type::Path(from_type_path),
+                            "as_ref",
+                        ),
+                        CastMutability::MutToConst => (
+                            parse_quote! {
+                                AsRef < #to_type >
+                            },
+                            parse_quote! {
+                                &'a mut ::core::pin::Pin < &'a mut #from_type_path >
+                            },
+                            "as_ref",
+                        ),
+                        CastMutability::MutToMut => (
+                            parse_quote! {
+                                autocxx::PinMut < #to_type >
+                            },
+                            parse_quote! {
+                                ::core::pin::Pin < &'a mut #from_type_path >
+                            },
+                            "pin_mut",
+                        ),
+                    };
+                    let method_name = make_ident(method_name);
+                    Some((
+                        FnKind::TraitMethod {
+                            kind: TraitMethodKind::Cast,
+                            impl_for: from_type.clone(),
+                            details: Box::new(TraitMethodDetails {
+                                trt: TraitImplSignature {
+                                    ty: ty.into(),
+                                    trait_signature,
+                                    unsafety: None,
+                                },
+                                avoid_self: false,
+                                method_name,
+                                parameter_reordering: None,
+                                trait_call_is_unsafe: false,
+                                receiver: None,
+                                output: TraitMethodOutput::Unchanged,
+                            }),
                         },
-                        parse_quote! {
-                            &'a mut ::core::pin::Pin < &'a mut #from_type_path >
+                        ErrorContext::new_for_item(make_ident(&rust_name)),
+                        rust_name,
+                    ))
+                }
+                TraitSynthesis::AllocUninitialized(ty) => self.generate_alloc_or_deallocate(
+                    ideal_rust_name,
+                    ty,
+                    "allocate_uninitialized_cpp_storage",
+                    TraitMethodKind::Alloc,
+                ),
+                TraitSynthesis::FreeUninitialized(ty) => self.generate_alloc_or_deallocate(
+                    ideal_rust_name,
+                    ty,
+                    "free_uninitialized_cpp_storage",
+                    TraitMethodKind::Dealloc,
+                ),
+                TraitSynthesis::Hash(ty) => {
+                    let rust_name =
+                        self.get_function_overload_name(ns, ideal_rust_name.to_string());
+                    Some((
+                        FnKind::TraitMethod {
+                            kind: TraitMethodKind::Hash,
+                            impl_for: ty.clone(),
+                            details: Box::new(TraitMethodDetails {
+                                trt: TraitImplSignature {
+                                    ty: Type::Path(ty.to_type_path()).into(),
+                                    trait_signature: parse_quote! { ::core::hash::Hash },
+                                    unsafety: None,
+                                },
+                                avoid_self: false,
+                                method_name: make_ident("hash"),
+                                parameter_reordering: None,
+                                trait_call_is_unsafe: false,
+                                receiver: Some(TraitReceiver::Reference),
+                                output: TraitMethodOutput::Hashed,
+                            }),
                         },
-                        "as_ref",
-                    ),
-                    CastMutability::MutToMut => (
-                        parse_quote! {
-                            autocxx::PinMut < #to_type >
+                        ErrorContext::new_for_item(make_ident(&rust_name)),
+                        rust_name,
+                    ))
+                }
+                TraitSynthesis::IntoIterator {
+                    container,
+                    state,
+                    mutable,
+                    ..
+                } => {
+                    let rust_name =
+                        self.get_function_overload_name(ns, ideal_rust_name.to_string());
+                    let container_path = container.to_type_path();
+                    let ty: Type = if *mutable {
+                        parse_quote! { ::core::pin::Pin<&'a mut #container_path> }
+                    } else {
+                        parse_quote! { &'a #container_path }
+                    };
+                    Some((
+                        FnKind::TraitMethod {
+                            kind: TraitMethodKind::Iterator,
+                            impl_for: container.clone(),
+                            details: Box::new(TraitMethodDetails {
+                                trt: TraitImplSignature {
+                                    ty: ty.into(),
+                                    trait_signature: parse_quote! { ::core::iter::IntoIterator },
+                                    unsafety: None,
+                                },
+                                avoid_self: false,
+                                method_name: make_ident("into_iter"),
+                                parameter_reordering: None,
+                                trait_call_is_unsafe: false,
+                                receiver: Some(TraitReceiver::Value),
+                                output: TraitMethodOutput::IntoIterator {
+                                    state: state.clone(),
+                                    mutable: *mutable,
+                                },
+                            }),
                         },
-                        parse_quote! {
-                            ::core::pin::Pin < &'a mut #from_type_path >
+                        ErrorContext::new_for_item(make_ident(&rust_name)),
+                        rust_name,
+                    ))
+                }
+                TraitSynthesis::IteratorState(state) => {
+                    let rust_name =
+                        self.get_function_overload_name(ns, ideal_rust_name.to_string());
+                    Some((
+                        FnKind::TraitMethod {
+                            kind: TraitMethodKind::Iterator,
+                            impl_for: state.clone(),
+                            details: Box::new(TraitMethodDetails {
+                                trt: TraitImplSignature {
+                                    ty: Type::Path(state.to_type_path()).into(),
+                                    trait_signature: parse_quote! { autocxx::CppIteratorState },
+                                    unsafety: Some(parse_quote! { unsafe }),
+                                },
+                                avoid_self: false,
+                                method_name: make_ident("next_item"),
+                                parameter_reordering: None,
+                                trait_call_is_unsafe: false,
+                                receiver: None,
+                                output: TraitMethodOutput::IteratorItem,
+                            }),
                         },
-                        "pin_mut",
-                    ),
-                };
-                let method_name = make_ident(method_name);
-                Some((
-                    FnKind::TraitMethod {
-                        kind: TraitMethodKind::Cast,
-                        impl_for: from_type.clone(),
-                        details: Box::new(TraitMethodDetails {
-                            trt: TraitImplSignature {
-                                ty: ty.into(),
-                                trait_signature,
-                                unsafety: None,
-                            },
-                            avoid_self: false,
-                            method_name,
-                            parameter_reordering: None,
-                            trait_call_is_unsafe: false,
-                        }),
-                    },
-                    ErrorContext::new_for_item(make_ident(&rust_name)),
-                    rust_name,
-                ))
-            }
-            TraitSynthesis::AllocUninitialized(ty) => self.generate_alloc_or_deallocate(
-                ideal_rust_name,
-                ty,
-                "allocate_uninitialized_cpp_storage",
-                TraitMethodKind::Alloc,
-            ),
-            TraitSynthesis::FreeUninitialized(ty) => self.generate_alloc_or_deallocate(
-                ideal_rust_name,
-                ty,
-                "free_uninitialized_cpp_storage",
-                TraitMethodKind::Dealloc,
-            ),
-        })
+                        ErrorContext::new_for_item(make_ident(&rust_name)),
+                        rust_name,
+                    ))
+                }
+                TraitSynthesis::Operator(op) => self.trait_creation_details_for_operator(
+                    *op,
+                    ns,
+                    ideal_rust_name,
+                    self_ty,
+                    param_details,
+                    &fun.output.0,
+                ),
+            })
     }
 
-    fn generate_alloc_or_deallocate(
+    /// Work out whether a C++ operator overload can become an implementation
+    /// of one of the `std::ops` or `std::cmp` traits. If it's not of a
+    /// suitable shape, it stays as a plain function or method.
+    fn trait_creation_details_for_operator(
         &mut self,
+        op: CppOperator,
+        ns: &Namespace,
         ideal_rust_name: &str,
-        ty: &QualifiedName,
-        method_name: &str,
-        kind: TraitMethodKind,
+        self_ty: &Option<QualifiedName>,
+        param_details: &[ArgumentAnalysis],
+        output: &ReturnType,
     ) -> Option<(FnKind, ErrorContext, String)> {
-        let rust_name =
-            self.get_function_overload_name(ty.get_namespace(), ideal_rust_name.to_string());
-        let typ = ty.to_type_path();
-        Some((
-            FnKind::TraitMethod {
-                impl_for: ty.clone(),
-                details: Box::new(TraitMethodDetails {
-                    trt: TraitImplSignature {
-                        ty: Type::Path(typ).into(),
-                        trait_signature: parse_quote! { autocxx::moveit::MakeCppStorage },
-                        unsafety: Some(parse_quote! { unsafe }),
+        let (trait_mod, trait_name, method_name, receiver, trait_output) =
+            match (op, param_details.len()) {
+                (CppOperator::Add, 2) => (
+                    "ops",
+                    "Add",
+                    "add",
+                    TraitReceiver::Value,
+                    TraitMethodOutput::AssociatedType,
+                ),
+                (CppOperator::Sub, 2) => (
+                    "ops",
+                    "Sub",
+                    "sub",
+                    TraitReceiver::Value,
+                    TraitMethodOutput::AssociatedType,
+                ),
+                (CppOperator::Mul, 2) => (
+                    "ops",
+                    "Mul",
+                    "mul",
+                    TraitReceiver::Value,
+                    TraitMethodOutput::AssociatedType,
+                ),
+                (CppOperator::Div, 2) => (
+                    "ops",
+                    "Div",
+                    "div",
+                    TraitReceiver::Value,
+                    TraitMethodOutput::AssociatedType,
+                ),
+                (CppOperator::Sub, 1) => (
+                    "ops",
+                    "Neg",
+                    "neg",
+                    TraitReceiver::Value,
+                    TraitMethodOutput::AssociatedType,
+                ),
+                (CppOperator::AddAssign, 2) => (
+                    "ops",
+                    "AddAssign",
+                    "add_assign",
+                    TraitReceiver::PinnedMutableReference,
+                    TraitMethodOutput::Discarded,
+                ),
+                (CppOperator::Index, 2) => (
+                    "ops",
+                    "Index",
+                    "index",
+                    TraitReceiver::Reference,
+                    TraitMethodOutput::AssociatedType,
+                ),
+                (CppOperator::Eq, 2) => (
+                    "cmp",
+                    "PartialEq",
+                    "eq",
+                    TraitReceiver::Reference,
+                    TraitMethodOutput::Unchanged,
+                ),
+                (CppOperator::Ne, 2) => (
+                    "cmp",
+                    "PartialEq",
+                    "ne",
+                    TraitReceiver::Reference,
+                    TraitMethodOutput::Unchanged,
+                ),
+                // `operator<` alone doesn't promise that values which
+                // aren't less than each other are equal, so this ordering
+                // isn't total.
+                (CppOperator::Lt, 2) => (
+                    "cmp",
+                    "PartialOrd",
+                    "partial_cmp",
+                    TraitReceiver::Reference,
+                    TraitMethodOutput::Ordering {
+                        total: false,
+                        operator: ComparisonOperator::LessThan,
                     },
-                    avoid_self: false,
-                    method_name: make_ident(method_name),
-                    parameter_reordering: None,
-                    trait_call_is_unsafe: false,
+                ),
+                (CppOperator::Cmp, 2) => (
+                    "cmp",
+                    "PartialOrd",
+                    "partial_cmp",
+                    TraitReceiver::Reference,
+                    TraitMethodOutput::Ordering {
+                        total: returns_strong_ordering(output),
+                        operator: ComparisonOperator::ThreeWay,
+                    },
+                ),
+                // By now, streaming operators no longer take the stream.
+                (CppOperator::Shl, 1) => (
+                    "fmt",
+                    "Display",
+                    "fmt",
+                    TraitReceiver::Reference,
+                    TraitMethodOutput::Formatted,
+                ),
+                _ => return None,
+            };
+        // Every parameter type needs to appear in the trait signature, so
+        // we can't cope with anything which needs Rust-side conversion.
+        // Nor can trait methods be unsafe.
+        if param_details.iter().any(|pd| {
+            pd.conversion.rust_work_needed() || matches!(pd.requires_unsafe, UnsafetyNeeded::Always)
+        }) {
+            return None;
+        }
+        let returns_reference = match output {
+            ReturnType::Type(_, ty) => type_is_reference(ty, false),
+            ReturnType::Default => false,
+        };
+        let returns_value = match output {
+            ReturnType::Type(_, ty) => !returns_reference && !matches!(**ty, Type::Ptr(_)),
+            ReturnType::Default => false,
+        };
+        let returns_bool = matches!(output, ReturnType::Type(_, ty)
+            if matches!(&**ty, Type::Path(typ) if typ.path.is_ident("bool")));
+        let return_shape_ok = match op {
+            CppOperator::AddAssign => true,
+            CppOperator::Index => returns_reference,
+            CppOperator::Eq | CppOperator::Ne | CppOperator::Lt => returns_bool,
+            _ => returns_value,
+        };
+        if !return_shape_ok {
+            return None;
+        }
+        let first_param = param_details.first()?;
+        let first_param_ty = first_param.conversion.converted_rust_type();
+        let ty = match receiver {
+            TraitReceiver::Value => first_param_ty,
+            TraitReceiver::Reference => shared_referent(first_param_ty)?,
+            TraitReceiver::PinnedMutableReference if first_param.is_mutable_reference => {
+                first_param_ty
+            }
+            _ => return None,
+        };
+        // The comparison traits take the other operand by reference too.
+        let rhs_ty = match param_details.get(1) {
+            Some(rhs) if trait_mod == "cmp" => {
+                Some(shared_referent(rhs.conversion.converted_rust_type())?)
+            }
+            Some(rhs) => Some(rhs.conversion.converted_rust_type()),
+            None => None,
+        };
+        // We compare both ways round, so the operands must be of the same type.
+        if matches!(trait_output, TraitMethodOutput::Ordering { .. })
+            && rhs_ty.as_ref().map(|rhs| rhs.to_token_stream().to_string())
+                != Some(ty.to_token_stream().to_string())
+        {
+            return None;
+        }
+        // For member operators we know the type already. For free functions,
+        // it's whatever the first parameter refers to.
+        let impl_for = match self_ty {
+            Some(self_ty) => self_ty.clone(),
+            None => operand_type_name(&ty)?,
+        };
+        // We'd otherwise implement `PartialOrd` twice.
+        if matches!(op, CppOperator::Lt) && self.three_way_comparable_types.contains(&impl_for) {
+            return None;
+        }
+        // The user may have told us that the ordering is total, even
+        // though C++ can't say so.
+        let trait_output = match trait_output {
+            TraitMethodOutput::Ordering { total, operator } => TraitMethodOutput::Ordering {
+                total: total || self.config.is_total_order_type(&impl_for.to_cpp_name()),
+                operator,
+            },
+            trait_output => trait_output,
+        };
+        let trait_mod = make_ident(trait_mod);
+        let trait_id = make_ident(trait_name);
+        let trait_signature = match rhs_ty {
+            Some(rhs_ty) => parse_quote! { ::core:: #trait_mod :: #trait_id < #rhs_ty > },
+            None => parse_quote! { ::core:: #trait_mod :: #trait_id },
+        };
+        let rust_name = match self_ty {
+            Some(self_ty) => {
+                self.get_overload_name(ns, self_ty.get_final_item(), ideal_rust_name.to_string())
+            }
+            None => self.get_function_overload_name(ns, ideal_rust_name.to_string()),
+        };
+        Some((
+            FnKind::TraitMethod {
+                kind: TraitMethodKind::Operator,
+                impl_for,
+                details: Box::new(TraitMethodDetails {
+                    trt: TraitImplSignature {
+                        ty: ty.into(),
+                        trait_signature,
+                        unsafety: None,
+                    },
+                    avoid_self: false,
+                    method_name: make_ident(method_name),
+                    parameter_reordering: None,
+                    trait_call_is_unsafe: false,
+                    receiver: Some(receiver),
+                    output: trait_output,
+                }),
+            },
+            ErrorContext::new_for_item(make_ident(&rust_name)),
+            rust_name,
+        ))
+    }
+
+    fn generate_alloc_or_deallocate(
+        &mut self,
+        ideal_rust_name: &str,
+        ty: &QualifiedName,
+        method_name: &str,
+        kind: TraitMethodKind,
+    ) -> Option<(FnKind, ErrorContext, String)> {
+        let rust_name =
+            self.get_function_overload_name(ty.get_namespace(), ideal_rust_name.to_string());
+        let typ = ty.to_type_path();
+        Some((
+            FnKind::TraitMethod {
+                impl_for: ty.clone(),
+                details: Box::new(TraitMethodDetails {
+                    trt: TraitImplSignature {
+                        ty: Type::Path(typ).into(),
+                        trait_signature: parse_quote! { autocxx::moveit::MakeCppStorage },
+                        unsafety: Some(parse_quote! { unsafe }),
+                    },
+                    avoid_self: false,
+                    method_name: make_ident(method_name),
+                    parameter_reordering: None,
+                    trait_call_is_unsafe: false,
+                    receiver: None,
+                    output: TraitMethodOutput::Unchanged,
                 }),
                 kind,
             },
@@ -1659,6 +2445,7 @@ impl<'a> FnAnalyzer<'a> {
         force_rust_conversion: Option<RustConversionType>,
         sophistication: TypeConversionSophistication,
         construct_into_self: bool,
+        mutable_span: bool,
     ) -> Result<(FnArg, ArgumentAnalysis), ConvertErrorFromCpp> {
         Ok(match &arg.0 {
             syn::FnArg::Typed(pt) => {
@@ -1737,7 +2524,69 @@ impl<'a> FnAnalyzer<'a> {
                         force_rust_conversion,
                         Some(RustConversionType::FromPlacementParamToNewReturn)
                     );
-                let annotated_type = self.convert_boxed_type(ty_to_convert, ns)?;
+                // This pointer is created by our own wrapper function, so
+                // needn't make the function unsafe.
+                let is_optional_out_param = matches!(
+                    force_rust_conversion,
+                    Some(RustConversionType::FromOptionalOutParamToPtr)
+                );
+                let mut annotated_type = self.convert_boxed_type(ty_to_convert, ns)?;
+                // We take a `std::span` of `char`s to be a view of bytes,
+                // unless we're told it's mutable.
+                if mutable_span && matches!(annotated_type.kind, type_converter::TypeKind::ByteView)
+                {
+                    annotated_type.ty = Box::new(parse_quote! { &[::std::os::raw::c_char] });
+                    annotated_type.kind = type_converter::TypeKind::Span;
+                }
+                if matches!(annotated_type.kind, type_converter::TypeKind::Span) {
+                    let elem = match extract_slice_type(&annotated_type.ty) {
+                        // Rust slices can only contain types we can hold by value.
+                        Some(elem) if self.is_pod_safe_type(elem) => elem.clone(),
+                        _ => {
+                            return Err(ConvertErrorFromCpp::UnsupportedType(
+                                annotated_type.ty.to_token_stream().to_string(),
+                            ))
+                        }
+                    };
+                    if mutable_span {
+                        annotated_type.ty = Box::new(parse_quote! { &mut [#elem] });
+                    }
+                }
+                if matches!(
+                    annotated_type.kind,
+                    type_converter::TypeKind::ArrayReference
+                ) && !self.is_pod_safe_array_reference(&annotated_type.ty)
+                {
+                    // Likewise Rust arrays.
+                    return Err(ConvertErrorFromCpp::UnsupportedType(
+                        annotated_type.ty.to_token_stream().to_string(),
+                    ));
+                }
+                match &annotated_type.kind {
+                    type_converter::TypeKind::Tuple(tuple_ty) => {
+                        self.confirm_tuple_is_pod_safe(tuple_ty)?
+                    }
+                    type_converter::TypeKind::Variant(details) => {
+                        self.add_variant_struct(&annotated_type.ty, details)
+                    }
+                    // Rust subclasses would need to turn a C function
+                    // pointer back into something callable, which we
+                    // don't support.
+                    type_converter::TypeKind::FunctionPointer(_)
+                        if matches!(
+                            sophistication,
+                            TypeConversionSophistication::SimpleForSubclasses
+                        ) =>
+                    {
+                        return Err(ConvertErrorFromCpp::UnsupportedType(
+                            annotated_type.ty.to_token_stream().to_string(),
+                        ));
+                    }
+                    type_converter::TypeKind::FunctionPointer(user_ty) => {
+                        self.confirm_function_pointer_is_pod_safe(user_ty)?
+                    }
+                    _ => {}
+                }
                 let conversion = self.argument_conversion_details(
                     &annotated_type,
                     is_move_constructor,
@@ -1746,19 +2595,35 @@ impl<'a> FnAnalyzer<'a> {
                     self_type.is_some(),
                     is_placement_return_destination,
                 );
+                let is_mutable_reference = match annotated_type.kind {
+                    type_converter::TypeKind::MutableReference => true,
+                    type_converter::TypeKind::Span | type_converter::TypeKind::ArrayReference => {
+                        matches!(
+                            annotated_type.ty.as_ref(),
+                            Type::Reference(TypeReference {
+                                mutability: Some(_),
+                                ..
+                            })
+                        )
+                    }
+                    _ => false,
+                };
                 let new_ty = annotated_type.ty;
                 pt.pat = Box::new(new_pat.clone());
                 pt.ty = new_ty;
-                let requires_unsafe =
-                    if matches!(annotated_type.kind, type_converter::TypeKind::Pointer)
-                        && !is_placement_return_destination
-                    {
-                        UnsafetyNeeded::Always
-                    } else if conversion.bridge_unsafe_needed() || is_placement_return_destination {
-                        UnsafetyNeeded::JustBridge
-                    } else {
-                        UnsafetyNeeded::None
-                    };
+                let requires_unsafe = if matches!(
+                    annotated_type.kind,
+                    type_converter::TypeKind::Pointer
+                        | type_converter::TypeKind::FunctionPointer(_)
+                ) && !is_placement_return_destination
+                    && !is_optional_out_param
+                {
+                    UnsafetyNeeded::Always
+                } else if conversion.bridge_unsafe_needed() || is_placement_return_destination {
+                    UnsafetyNeeded::JustBridge
+                } else {
+                    UnsafetyNeeded::None
+                };
                 (
                     syn::FnArg::Typed(pt).into(),
                     ArgumentAnalysis {
@@ -1769,11 +2634,11 @@ impl<'a> FnAnalyzer<'a> {
                             annotated_type.kind,
                             type_converter::TypeKind::Reference
                                 | type_converter::TypeKind::MutableReference
+                                | type_converter::TypeKind::ByteView
+                                | type_converter::TypeKind::Span
+                                | type_converter::TypeKind::ArrayReference
                         ),
-                        is_mutable_reference: matches!(
-                            annotated_type.kind,
-                            type_converter::TypeKind::MutableReference
-                        ),
+                        is_mutable_reference,
                         deps: annotated_type.types_encountered,
                         requires_unsafe,
                         is_placement_return_destination,
@@ -1805,6 +2670,74 @@ impl<'a> FnAnalyzer<'a> {
             matches!(annotated_type.kind, type_converter::TypeKind::Reference) || is_self;
         let rust_conversion_forced = force_rust_conversion.is_some();
         let ty = &*annotated_type.ty;
+        if matches!(annotated_type.kind, type_converter::TypeKind::ByteView) {
+            return TypeConversionPolicy::new(
+                ty.clone(),
+                CppConversionType::FromSliceToByteView,
+                RustConversionType::FromAsRefBytes,
+            );
+        }
+        if matches!(annotated_type.kind, type_converter::TypeKind::Span) {
+            return TypeConversionPolicy::new(
+                ty.clone(),
+                CppConversionType::FromSliceToSpan,
+                RustConversionType::None,
+            );
+        }
+        if matches!(
+            annotated_type.kind,
+            type_converter::TypeKind::ArrayReference
+        ) {
+            return TypeConversionPolicy::new(
+                ty.clone(),
+                CppConversionType::FromStdArrayToCArray,
+                RustConversionType::None,
+            );
+        }
+        if let type_converter::TypeKind::FunctionPointer(user_ty) = &annotated_type.kind {
+            let bare_fn = Type::BareFn(extract_bare_fn(user_ty).clone());
+            return TypeConversionPolicy::new(
+                ty.clone(),
+                CppConversionType::FromVoidPtrToFunctionPointer(Box::new(bare_fn.into())),
+                RustConversionType::FromFunctionPointerToVoidPtr((**user_ty).clone()),
+            );
+        }
+        if let Some(user_ty) = Self::generated_struct_user_type(&annotated_type.kind) {
+            return TypeConversionPolicy::new(
+                ty.clone(),
+                CppConversionType::FromGeneratedStructToValue,
+                RustConversionType::FromValueToGeneratedStruct(user_ty.into()),
+            );
+        }
+        if matches!(
+            force_rust_conversion,
+            Some(RustConversionType::FromOptionalOutParamToPtr)
+        ) {
+            return TypeConversionPolicy::new(
+                ty.clone(),
+                CppConversionType::IgnoredPlacementPtrParameter,
+                RustConversionType::FromOptionalOutParamToPtr,
+            );
+        }
+        if let (type_converter::TypeKind::Optional, Some(inner)) =
+            (&annotated_type.kind, extract_option_type(ty))
+        {
+            // Values we can pass around in Rust are given to C++ by pointer;
+            // anything else must be in a `UniquePtr`.
+            return if self.is_pod_safe_type(inner) {
+                TypeConversionPolicy::new(
+                    parse_quote! { *const #inner },
+                    CppConversionType::FromPtrToOptional,
+                    RustConversionType::FromOptionToPtr,
+                )
+            } else {
+                TypeConversionPolicy::new(
+                    parse_quote! { cxx::UniquePtr<#inner> },
+                    CppConversionType::FromUniquePtrToOptional,
+                    RustConversionType::FromOptionToUniquePtr,
+                )
+            };
+        }
         if let Some(holder_id) = is_subclass_holder {
             let subclass = SubclassName::from_holder_name(holder_id);
             return {
@@ -1942,6 +2875,7 @@ impl<'a> FnAnalyzer<'a> {
         ns: &Namespace,
         diagnostic_name: &QualifiedName,
         sophistication: TypeConversionSophistication,
+        avoid_placement_return: bool,
     ) -> Result<ReturnTypeAnalysis, ConvertErrorFromCpp> {
         Ok(match rt {
             ReturnType::Default => ReturnTypeAnalysis::default(),
@@ -1949,6 +2883,61 @@ impl<'a> FnAnalyzer<'a> {
                 let annotated_type = self.convert_boxed_type(boxed_type.clone(), ns)?;
                 let boxed_type = annotated_type.ty;
                 let ty: &Type = boxed_type.as_ref();
+                if matches!(
+                    annotated_type.kind,
+                    type_converter::TypeKind::Span | type_converter::TypeKind::ArrayReference
+                ) {
+                    return Err(ConvertErrorFromCpp::UnsupportedType(
+                        ty.to_token_stream().to_string(),
+                    ));
+                }
+                // cxx can't return a function pointer, so our wrapper
+                // returns it as a `void*`.
+                if let type_converter::TypeKind::FunctionPointer(user_ty) = &annotated_type.kind {
+                    self.confirm_function_pointer_is_pod_safe(user_ty)?;
+                    return Ok(ReturnTypeAnalysis {
+                        conversion: Some(TypeConversionPolicy::new(
+                            ty.clone(),
+                            CppConversionType::FromFunctionPointerToVoidPtr,
+                            RustConversionType::FromVoidPtrToFunctionPointer((**user_ty).clone()),
+                        )),
+                        rt: ReturnType::Type(*rarrow, boxed_type),
+                        deps: annotated_type.types_encountered,
+                        ..Default::default()
+                    });
+                }
+                if let (type_converter::TypeKind::Optional, Some(inner)) =
+                    (&annotated_type.kind, extract_option_type(ty))
+                {
+                    return self.convert_optional_return_type(
+                        rarrow,
+                        inner,
+                        annotated_type.types_encountered,
+                        ns,
+                        diagnostic_name,
+                    );
+                }
+                match &annotated_type.kind {
+                    type_converter::TypeKind::Tuple(tuple_ty) => {
+                        self.confirm_tuple_is_pod_safe(tuple_ty)?
+                    }
+                    type_converter::TypeKind::Variant(details) => {
+                        self.add_variant_struct(ty, details)
+                    }
+                    _ => {}
+                }
+                if let Some(user_ty) = Self::generated_struct_user_type(&annotated_type.kind) {
+                    return Ok(ReturnTypeAnalysis {
+                        conversion: Some(TypeConversionPolicy::new(
+                            ty.clone(),
+                            CppConversionType::FromValueToGeneratedStruct,
+                            RustConversionType::FromGeneratedStructToValue(user_ty.into()),
+                        )),
+                        rt: ReturnType::Type(*rarrow, boxed_type),
+                        deps: annotated_type.types_encountered,
+                        ..Default::default()
+                    });
+                }
                 match ty {
                     Type::Path(p)
                         if !self
@@ -1958,6 +2947,7 @@ impl<'a> FnAnalyzer<'a> {
                         let tn = QualifiedName::from_type_path(p);
                         if self.moveit_safe_types.contains(&tn)
                             && matches!(sophistication, TypeConversionSophistication::Regular)
+                            && !avoid_placement_return
                         {
                             // This is a non-POD type we want to return to Rust as an `impl New` so that callers
                             // can decide whether to store this on the stack or heap.
@@ -1977,6 +2967,7 @@ impl<'a> FnAnalyzer<'a> {
                                 Some(RustConversionType::FromPlacementParamToNewReturn),
                                 TypeConversionSophistication::Regular,
                                 false,
+                                false,
                             )?;
                             ReturnTypeAnalysis {
                                 rt: ReturnType::Default,
@@ -1991,7 +2982,9 @@ impl<'a> FnAnalyzer<'a> {
                             // There are some types which we can't currently represent within a moveit::new::New.
                             // That's either because we are obliged to stick to existing protocols for compatibility
                             // (CxxString) or because they're a concrete type where we haven't attempted to do
-                            // the analysis to work out the type's size. For these, we always return a plain old
+                            // the analysis to work out the type's size. Functions which throw also can't
+                            // report errors from within a New, and operator traits need an `Output`
+                            // type we can name. For these, we always return a plain old
                             // UniquePtr<T>. These restrictions may be fixed in future.
                             let conversion =
                                 Some(TypeConversionPolicy::new_to_unique_ptr(ty.clone()));
@@ -2008,20 +3001,27 @@ impl<'a> FnAnalyzer<'a> {
                             annotated_type.kind,
                             type_converter::TypeKind::MutableReference
                         );
+                        let is_byte_view =
+                            matches!(annotated_type.kind, type_converter::TypeKind::ByteView);
                         let was_reference = was_mutable_reference
+                            || is_byte_view
                             || matches!(annotated_type.kind, type_converter::TypeKind::Reference);
-                        let conversion = Some(
-                            if was_reference
-                                && matches!(
-                                    self.config.unsafe_policy,
-                                    UnsafePolicy::ReferencesWrappedAllFunctionsSafe
-                                )
-                            {
-                                TypeConversionPolicy::return_reference_into_wrapper(ty.clone())
-                            } else {
-                                TypeConversionPolicy::new_unconverted(ty.clone())
-                            },
-                        );
+                        let conversion = Some(if is_byte_view {
+                            TypeConversionPolicy::new(
+                                ty.clone(),
+                                CppConversionType::FromByteViewToSlice,
+                                RustConversionType::None,
+                            )
+                        } else if was_reference
+                            && matches!(
+                                self.config.unsafe_policy,
+                                UnsafePolicy::ReferencesWrappedAllFunctionsSafe
+                            )
+                        {
+                            TypeConversionPolicy::return_reference_into_wrapper(ty.clone())
+                        } else {
+                            TypeConversionPolicy::new_unconverted(ty.clone())
+                        });
                         ReturnTypeAnalysis {
                             rt: ReturnType::Type(*rarrow, boxed_type),
                             conversion,
@@ -2036,6 +3036,377 @@ impl<'a> FnAnalyzer<'a> {
         })
     }
 
+    /// A `std::optional<T>` is returned as a `UniquePtr<T>` which may be null,
+    /// unless `T` is something we can hold in Rust, in which case we ask C++
+    /// to put it into a placement parameter and tell us whether it did so.
+    fn convert_optional_return_type(
+        &mut self,
+        rarrow: &syn::token::RArrow,
+        inner: &Type,
+        deps: HashSet<QualifiedName>,
+        ns: &Namespace,
+        diagnostic_name: &QualifiedName,
+    ) -> Result<ReturnTypeAnalysis, ConvertErrorFromCpp> {
+        Ok(if self.is_pod_safe_type(inner) {
+            let fnarg = parse_quote! {
+                optional_out: *mut #inner
+            };
+            let (fnarg, analysis) = self.convert_fn_arg(
+                &fnarg,
+                ns,
+                diagnostic_name,
+                &None,
+                false,
+                false,
+                Some(RustConversionType::FromOptionalOutParamToPtr),
+                TypeConversionSophistication::Regular,
+                false,
+                false,
+            )?;
+            ReturnTypeAnalysis {
+                rt: parse_quote! { #rarrow bool },
+                conversion: Some(TypeConversionPolicy::new(
+                    inner.clone(),
+                    CppConversionType::FromOptionalToPlacementPtr,
+                    RustConversionType::FromBoolToOption,
+                )),
+                deps,
+                placement_param_needed: Some((fnarg, analysis)),
+                ..Default::default()
+            }
+        } else {
+            let unique_ptr: Type = parse_quote! { cxx::UniquePtr<#inner> };
+            ReturnTypeAnalysis {
+                rt: parse_quote! { #rarrow #unique_ptr },
+                conversion: Some(TypeConversionPolicy::new(
+                    unique_ptr,
+                    CppConversionType::FromOptionalToUniquePtr,
+                    RustConversionType::FromUniquePtrToOption,
+                )),
+                deps,
+                ..Default::default()
+            }
+        })
+    }
+
+    /// Converts a `std::function` parameter whose signature the user has
+    /// declared using `std_function!`, ignoring the type which bindgen
+    /// gives it. As for a C function pointer, we pass a `void*` through
+    /// cxx, and the C++ wrapper makes a `std::function` which takes
+    /// ownership of the Rust closure to which it points.
+    fn convert_std_function_arg(
+        &mut self,
+        arg: &FnArg,
+        ns: &Namespace,
+        param_types: &[Type],
+        output: &ReturnType,
+        sophistication: TypeConversionSophistication,
+    ) -> Result<(FnArg, ArgumentAnalysis), ConvertErrorFromCpp> {
+        let mut pt = match &arg.0 {
+            syn::FnArg::Typed(pt) => pt.clone(),
+            _ => panic!("Did not expect FnArg::Receiver to be generated by bindgen"),
+        };
+        if let syn::Pat::Ident(pp) = pt.pat.as_ref() {
+            validate_ident_ok_for_cxx(&pp.ident.to_string())
+                .map_err(ConvertErrorFromCpp::InvalidIdent)?;
+        }
+        // We can only check that this is some sort of `std::function`; the
+        // generated C++ checks that it's of the signature we were given.
+        let is_std_function_param = match pt.ty.as_ref() {
+            Type::Path(typ) => match (unwrap_reference(typ, false), unwrap_reference(typ, true)) {
+                (Some(ptr), _) => ptr.mutability.is_none() && is_std_function(&ptr.elem),
+                (None, Some(ptr)) => is_std_function(&ptr.elem),
+                (None, None) => is_std_function(&pt.ty),
+            },
+            _ => false,
+        };
+        if !is_std_function_param {
+            return Err(ConvertErrorFromCpp::UnsupportedType(
+                pt.ty.to_token_stream().to_string(),
+            ));
+        }
+        // Describe the signature as bindgen would describe a C function
+        // pointer, so that we can convert it in just the same way.
+        let param_types = param_types.iter().map(to_bindgen_type);
+        let output = match output {
+            ReturnType::Type(arrow, ty) if !matches!(ty.as_ref(), Type::Tuple(tup) if tup.elems.is_empty()) => {
+                ReturnType::Type(*arrow, Box::new(to_bindgen_type(ty)))
+            }
+            _ => ReturnType::Default,
+        };
+        let fn_ptr: Type = parse_quote! {
+            ::std::option::Option<unsafe extern "C" fn(#(#param_types),*) #output>
+        };
+        let annotated_type = self.convert_boxed_type(Box::new(fn_ptr), ns)?;
+        let user_ty = match &annotated_type.kind {
+            // Rust subclasses would need to turn a `std::function` back
+            // into a closure, which we don't support.
+            type_converter::TypeKind::FunctionPointer(user_ty)
+                if !matches!(
+                    sophistication,
+                    TypeConversionSophistication::SimpleForSubclasses
+                ) =>
+            {
+                user_ty
+            }
+            _ => {
+                return Err(ConvertErrorFromCpp::UnsupportedType(
+                    pt.ty.to_token_stream().to_string(),
+                ))
+            }
+        };
+        self.confirm_function_pointer_is_pod_safe(user_ty)?;
+        let bare_fn = extract_bare_fn(user_ty);
+        let details = Box::new(StdFunctionDetails {
+            param_types: bare_fn
+                .inputs
+                .iter()
+                .map(|input| input.ty.clone().into())
+                .collect(),
+            output: bare_fn.output.clone().into(),
+        });
+        let ty = annotated_type.ty;
+        pt.ty = ty.clone();
+        Ok((
+            syn::FnArg::Typed(pt.clone()).into(),
+            ArgumentAnalysis {
+                conversion: TypeConversionPolicy::new(
+                    *ty,
+                    CppConversionType::FromVoidPtrToStdFunction(details.clone()),
+                    RustConversionType::FromClosureToStdFunction(details),
+                ),
+                name: (*pt.pat).into(),
+                self_type: None,
+                has_lifetime: false,
+                is_mutable_reference: false,
+                deps: annotated_type.types_encountered,
+                requires_unsafe: UnsafetyNeeded::JustBridge,
+                is_placement_return_destination: false,
+            },
+        ))
+    }
+
+    /// Converts a `std::array` parameter, passed by value or by reference,
+    /// whose element type and size the user has declared using
+    /// `std_array!`, since bindgen can't tell us them. cxx passes a Rust
+    /// array as a `std::array`, so no conversion is needed.
+    fn convert_std_array_arg(
+        &mut self,
+        arg: &FnArg,
+        ns: &Namespace,
+        array: &syn::TypeArray,
+    ) -> Result<(FnArg, ArgumentAnalysis), ConvertErrorFromCpp> {
+        let mut pt = match &arg.0 {
+            syn::FnArg::Typed(pt) => pt.clone(),
+            _ => panic!("Did not expect FnArg::Receiver to be generated by bindgen"),
+        };
+        if let syn::Pat::Ident(pp) = pt.pat.as_ref() {
+            validate_ident_ok_for_cxx(&pp.ident.to_string())
+                .map_err(ConvertErrorFromCpp::InvalidIdent)?;
+        }
+        let (std_array_ty, mutability) = match pt.ty.as_ref() {
+            Type::Path(typ) => match unwrap_reference(typ, false) {
+                Some(ptr) => (ptr.elem.as_ref(), Some(ptr.mutability)),
+                None => (pt.ty.as_ref(), None),
+            },
+            _ => (pt.ty.as_ref(), None),
+        };
+        let (array_ty, deps) = self.convert_std_array(std_array_ty, array, ns)?;
+        let ty: Type = match mutability {
+            Some(mutability) => parse_quote! { & #mutability #array_ty },
+            None => array_ty,
+        };
+        let is_mutable_reference = matches!(mutability, Some(Some(_)));
+        *pt.ty = ty.clone();
+        Ok((
+            syn::FnArg::Typed(pt.clone()).into(),
+            ArgumentAnalysis {
+                conversion: TypeConversionPolicy::new_unconverted(ty),
+                name: (*pt.pat).into(),
+                self_type: None,
+                has_lifetime: mutability.is_some(),
+                is_mutable_reference,
+                deps,
+                requires_unsafe: UnsafetyNeeded::None,
+                is_placement_return_destination: false,
+            },
+        ))
+    }
+
+    /// Converts a `std::array` return value declared using `std_array!`.
+    fn convert_std_array_return(
+        &mut self,
+        rt: &ReturnType,
+        ns: &Namespace,
+        array: &syn::TypeArray,
+    ) -> Result<ReturnTypeAnalysis, ConvertErrorFromCpp> {
+        let (rarrow, ty) = match rt {
+            ReturnType::Type(rarrow, ty) => (rarrow, ty),
+            ReturnType::Default => {
+                return Err(ConvertErrorFromCpp::UnsupportedType("()".to_string()))
+            }
+        };
+        let (array_ty, deps) = self.convert_std_array(ty, array, ns)?;
+        Ok(ReturnTypeAnalysis {
+            rt: ReturnType::Type(*rarrow, Box::new(array_ty.clone())),
+            conversion: Some(TypeConversionPolicy::new_unconverted(array_ty)),
+            deps,
+            ..Default::default()
+        })
+    }
+
+    /// Checks that bindgen's description of a type could be a
+    /// `std::array`, and works out the Rust array type for the element
+    /// type and size given in `std_array!`. cxx can only pass arrays of
+    /// things which Rust can hold by value.
+    fn convert_std_array(
+        &mut self,
+        ty: &Type,
+        array: &syn::TypeArray,
+        ns: &Namespace,
+    ) -> Result<(Type, HashSet<QualifiedName>), ConvertErrorFromCpp> {
+        if !is_std_array(ty) {
+            return Err(ConvertErrorFromCpp::UnsupportedType(
+                ty.to_token_stream().to_string(),
+            ));
+        }
+        let elem = self.convert_boxed_type(Box::new(to_bindgen_type(&array.elem)), ns)?;
+        if !matches!(elem.kind, type_converter::TypeKind::Regular)
+            || !self.is_pod_safe_type(&elem.ty)
+        {
+            return Err(ConvertErrorFromCpp::UnsupportedType(
+                array.to_token_stream().to_string(),
+            ));
+        }
+        let elem_ty = elem.ty;
+        let len = &array.len;
+        Ok((parse_quote! { [#elem_ty; #len] }, elem.types_encountered))
+    }
+
+    /// We can only pass a `std::pair` or `std::tuple` by value if we can
+    /// do the same for each of its elements.
+    fn confirm_tuple_is_pod_safe(&self, tuple_ty: &Type) -> Result<(), ConvertErrorFromCpp> {
+        match tuple_ty {
+            Type::Tuple(tuple) if tuple.elems.iter().all(|elem| self.is_pod_safe_type(elem)) => {
+                Ok(())
+            }
+            _ => Err(ConvertErrorFromCpp::UnsupportedType(
+                tuple_ty.to_token_stream().to_string(),
+            )),
+        }
+    }
+
+    /// A C function pointer can only be given Rust functions (or closures)
+    /// taking and returning things which Rust can hold by value, or
+    /// pointers or references.
+    fn confirm_function_pointer_is_pod_safe(
+        &self,
+        user_ty: &Type,
+    ) -> Result<(), ConvertErrorFromCpp> {
+        let bare_fn = extract_bare_fn(user_ty);
+        let output = match &bare_fn.output {
+            ReturnType::Default => None,
+            ReturnType::Type(_, ty) => Some(ty.as_ref()),
+        };
+        let types_ok = bare_fn
+            .inputs
+            .iter()
+            .map(|input| &input.ty)
+            .chain(output)
+            .all(|ty| !matches!(ty, Type::Path(_)) || self.is_pod_safe_type(ty));
+        if bare_fn.variadic.is_none() && types_ok {
+            Ok(())
+        } else {
+            Err(ConvertErrorFromCpp::UnsupportedType(
+                user_ty.to_token_stream().to_string(),
+            ))
+        }
+    }
+
+    /// Make the struct we use to pass a `std::variant` by value, if we
+    /// haven't already, now that we know which of its alternatives
+    /// can be held by value in Rust.
+    fn add_variant_struct(&mut self, ty: &Type, details: &VariantDetails) {
+        let name = match ty {
+            Type::Path(typ) => QualifiedName::from_type_path(typ),
+            _ => return,
+        };
+        if self.variant_structs.insert(name.clone()) {
+            let alternatives = details
+                .alternatives
+                .iter()
+                .map(|(alt_name, alt_ty)| VariantAlternative {
+                    name: alt_name.clone(),
+                    ty: alt_ty.clone(),
+                    by_value: self.is_pod_safe_type(alt_ty),
+                })
+                .collect();
+            self.extra_apis.push(Api::VariantStruct {
+                name: ApiName::new_from_qualified_name(name),
+                enum_name: details.enum_name.clone(),
+                alternatives,
+            });
+        }
+    }
+
+    /// If this type is passed across the bridge in a struct we generate,
+    /// the type which users see instead.
+    fn generated_struct_user_type(kind: &type_converter::TypeKind) -> Option<Type> {
+        match kind {
+            type_converter::TypeKind::Tuple(tuple_ty) => Some((***tuple_ty).clone()),
+            type_converter::TypeKind::Variant(details) => {
+                let enum_name = QualifiedName::new(&Namespace::new(), details.enum_name.clone());
+                Some(Type::Path(enum_name.to_type_path()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether a reference to a C array refers to elements which we
+    /// can hold by value.
+    fn is_pod_safe_array_reference(&self, ty: &Type) -> bool {
+        let mut elem = match ty {
+            Type::Reference(TypeReference { elem, .. }) => elem.as_ref(),
+            _ => return false,
+        };
+        while let Type::Array(TypeArray { elem: inner, .. }) = elem {
+            elem = inner.as_ref();
+        }
+        self.is_pod_safe_type(elem)
+    }
+
+    /// Whether this is a `*mut *mut T` through which C++ can give us a
+    /// `T` to hold in a `UniquePtr`.
+    fn is_unique_ptr_out_param(&self, ty: &Type) -> bool {
+        let inner = match ty {
+            Type::Ptr(TypePtr {
+                mutability: Some(_),
+                elem,
+                ..
+            }) => elem.as_ref(),
+            _ => return false,
+        };
+        match inner {
+            Type::Ptr(TypePtr {
+                mutability: Some(_),
+                elem,
+                ..
+            }) => match elem.as_ref() {
+                Type::Path(typ) => {
+                    let qn = QualifiedName::from_type_path(typ);
+                    known_types().permissible_within_unique_ptr(&qn)
+                        && !self.type_converter.is_forward_declaration(&qn)
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn is_pod_safe_type(&self, ty: &Type) -> bool {
+        matches!(ty, Type::Path(typ) if self.pod_safe_types.contains(&QualifiedName::from_type_path(typ)))
+    }
+
     /// If a type has explicit constructors, bindgen will generate corresponding
     /// constructor functions, which we'll have already converted to make_unique methods.
     /// C++ mandates the synthesis of certain implicit constructors, to which we
@@ -2205,6 +3576,30 @@ fn special_member_to_string(special_member: SpecialMemberKind) -> &'static str {
     }
 }
 
+/// The fully-qualified C++ name by which users refer to this function
+/// or method in directives such as `default_params!` and `throws!`.
+fn name_for_directives(name: &ApiName, kind: &FnKind) -> String {
+    match kind {
+        FnKind::Function => name_for_directives_in(name, None),
+        FnKind::Method { impl_for, .. } | FnKind::TraitMethod { impl_for, .. } => {
+            name_for_directives_in(name, Some(impl_for))
+        }
+    }
+}
+
+/// The name by which directives refer to a function, or to a method of
+/// the given type.
+fn name_for_directives_in(name: &ApiName, impl_for: Option<&QualifiedName>) -> String {
+    match impl_for {
+        None => name.qualified_cpp_name(),
+        Some(impl_for) => format!(
+            "{}::{}",
+            impl_for.to_cpp_name(),
+            name.cpp_name().to_string_for_cpp_generation()
+        ),
+    }
+}
+
 /// Attempts to determine whether this function name is a constructor, and if so,
 /// returns the suffix.
 fn constructor_with_suffix<'a>(rust_name: &'a str, nested_type_ident: &str) -> Option<&'a str> {
@@ -2224,9 +3619,13 @@ impl Api<FnPhase> {
             Api::Function { fun, analysis, .. } => match analysis.kind {
                 FnKind::Method { ref impl_for, .. } => impl_for.clone(),
                 FnKind::TraitMethod { ref impl_for, .. } => impl_for.clone(),
-                FnKind::Function => {
-                    QualifiedName::new(self.name().get_namespace(), fun.ident.clone())
-                }
+                FnKind::Function => match &fun.synthetic_cpp {
+                    Some((
+                        CppFunctionBody::StaticData(var) | CppFunctionBody::StaticDataAddress(var),
+                        _,
+                    )) => var.clone(),
+                    _ => QualifiedName::new(self.name().get_namespace(), fun.ident.clone()),
+                },
             },
             Api::RustSubclassFn { subclass, .. } => subclass.0.name.clone(),
             Api::IgnoredItem {
@@ -2264,6 +3663,8 @@ impl Api<FnPhase> {
                 ..
             } | Api::StringConstructor { .. }
                 | Api::ConcreteType { .. }
+                | Api::TupleStruct { .. }
+                | Api::VariantStruct { .. }
                 | Api::CType { .. }
                 | Api::RustSubclassFn { .. }
                 | Api::Subclass { .. }
@@ -2292,14 +3693,54 @@ impl Api<FnPhase> {
     }
 }
 
-fn return_type_is_reference(output: &crate::minisyn::ReturnType) -> bool {
-    if let ReturnType::Type(_, ty) = &output.0 {
-        type_is_reference(ty.as_ref(), true)
-    } else {
-        false
+/// If this function returns an rvalue reference, the type to which it refers.
+fn unwrap_rvalue_reference_return(
+    output: &crate::minisyn::ReturnType,
+) -> Option<crate::minisyn::ReturnType> {
+    match &output.0 {
+        ReturnType::Type(arrow, ty) => match ty.as_ref() {
+            Type::Path(typ) => unwrap_reference(typ, true)
+                .map(|ptr| ReturnType::Type(*arrow, ptr.elem.clone()).into()),
+            _ => None,
+        },
+        ReturnType::Default => None,
+    }
+}
+
+/// The function type within the `Option<unsafe extern "C" fn(...)>` which
+/// we show users for a C function pointer.
+fn extract_bare_fn(user_ty: &Type) -> &TypeBareFn {
+    match extract_option_type(user_ty) {
+        Some(Type::BareFn(bare_fn)) => bare_fn,
+        _ => panic!("Function pointer type was not an Option<fn>"),
     }
 }
 
+/// Whether this is a `void*` such as a callback may be given as user data.
+fn is_void_ptr(ty: &Type) -> bool {
+    matches!(ty, Type::Ptr(TypePtr { mutability: Some(_), elem, .. })
+        if matches!(elem.as_ref(), Type::Path(typ)
+            if typ.path.segments.last().is_some_and(|seg| seg.ident == "c_void")))
+}
+
+/// If this function pointer type could be a callback taking a `void*` of
+/// user data, describes its signature so that we can instead take a Rust
+/// closure. The first `void*` parameter is assumed to be the user data.
+fn callback_details(user_ty: &Type) -> Option<CallbackDetails> {
+    let bare_fn = extract_bare_fn(user_ty);
+    let param_types: Vec<crate::minisyn::Type> = bare_fn
+        .inputs
+        .iter()
+        .map(|input| input.ty.clone().into())
+        .collect();
+    let user_data_idx = param_types.iter().position(|ty| is_void_ptr(ty))?;
+    Some(CallbackDetails {
+        param_types,
+        user_data_idx,
+        output: bare_fn.output.clone().into(),
+    })
+}
+
 fn extract_type_from_pinned_mut_ref(ty: &TypePath) -> Type {
     match ty
         .path
@@ -2321,6 +3762,87 @@ fn extract_type_from_pinned_mut_ref(ty: &TypePath) -> Type {
     }
 }
 
+/// The type referred to by a `&T`.
+fn shared_referent(ty: Type) -> Option<Type> {
+    match ty {
+        Type::Reference(TypeReference {
+            elem,
+            mutability: None,
+            ..
+        }) => Some(*elem),
+        _ => None,
+    }
+}
+
+/// Whether bindgen's description of a type is a `std::function`, of any
+/// signature.
+fn is_std_function(ty: &Type) -> bool {
+    match ty {
+        Type::Path(typ) => match unwrap_has_opaque(typ) {
+            Some(inner) => is_std_function(inner),
+            None => QualifiedName::from_type_path(typ).to_cpp_name() == "std::function",
+        },
+        _ => false,
+    }
+}
+
+/// Whether bindgen's description of a type could be a `std::array`. It's
+/// opaque, since it has a non-type template parameter, so we can't tell
+/// its element type or size.
+fn is_std_array(ty: &Type) -> bool {
+    match ty {
+        Type::Path(typ) => match unwrap_has_opaque(typ) {
+            Some(_) => true,
+            None => QualifiedName::from_type_path(typ).to_cpp_name() == "std::array",
+        },
+        _ => false,
+    }
+}
+
+/// Whether an `operator<=>` returns a `std::strong_ordering`, i.e. values
+/// which compare equal are interchangeable and all values are ordered.
+fn returns_strong_ordering(output: &ReturnType) -> bool {
+    matches!(output, ReturnType::Type(_, ty)
+        if matches!(&**ty, Type::Path(typ)
+            if QualifiedName::from_type_path(typ).to_cpp_name() == "std::strong_ordering"))
+}
+
+/// Find the type on which a free-function operator acts, before any type
+/// conversion, given that it takes its first parameter by reference as
+/// bindgen represents it.
+fn first_operand_type_name(fun: &FuncToConvert, ns: &Namespace) -> Option<QualifiedName> {
+    let ptr = match fun.inputs.first().map(|arg| &arg.0) {
+        Some(syn::FnArg::Typed(pt)) => match pt.ty.as_ref() {
+            Type::Path(typ) => unwrap_reference(typ, false)?,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    match ptr.elem.as_ref() {
+        // bindgen may refer to types in the same namespace without
+        // qualification.
+        Type::Path(typ) if typ.path.segments.len() == 1 => Some(QualifiedName::new(
+            ns,
+            typ.path.segments[0].ident.clone().into(),
+        )),
+        Type::Path(typ) => Some(QualifiedName::from_type_path(typ)),
+        _ => None,
+    }
+}
+
+/// Find the type on which an operator acts, given the Rust type of
+/// its first parameter: `T`, `&T` or `Pin<&mut T>`.
+fn operand_type_name(ty: &Type) -> Option<QualifiedName> {
+    match ty {
+        Type::Reference(TypeReference { elem, .. }) => operand_type_name(elem),
+        Type::Path(typ) => match extract_pinned_mutable_reference_type(typ) {
+            Some(ty) => operand_type_name(ty),
+            None => Some(QualifiedName::from_type_path(typ)),
+        },
+        _ => None,
+    }
+}
+
 impl HasFieldsAndBases for Api<FnPrePhase1> {
     fn name(&self) -> &QualifiedName {
         self.name()
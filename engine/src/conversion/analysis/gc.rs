@@ -16,7 +16,10 @@ use crate::{
     types::QualifiedName,
 };
 
-use super::{deps::HasDependencies, fun::FnPhase};
+use super::{
+    deps::HasDependencies,
+    fun::{FnAnalysis, FnKind, FnPhase},
+};
 
 /// This is essentially mark-and-sweep garbage collection of the
 /// [Api]s that we've discovered. Why do we do this, you might wonder?
@@ -48,6 +51,32 @@ pub(crate) fn filter_apis_by_following_edges_from_allowlist(
         .map(Api::name)
         .cloned()
         .collect();
+    // Concrete template types have no methods except those we make for them,
+    // so keep those whenever we keep the type.
+    let concrete_types: HashSet<QualifiedName> = apis
+        .iter()
+        .filter(|api| matches!(api, Api::ConcreteType { .. }))
+        .map(|api| api.name().clone())
+        .collect();
+    let mut methods_by_concrete_type: HashMap<QualifiedName, Vec<QualifiedName>> = HashMap::new();
+    for api in apis.iter() {
+        if let Api::Function {
+            analysis:
+                FnAnalysis {
+                    kind: FnKind::Method { impl_for, .. } | FnKind::TraitMethod { impl_for, .. },
+                    ..
+                },
+            ..
+        } = api
+        {
+            if concrete_types.contains(impl_for) {
+                methods_by_concrete_type
+                    .entry(impl_for.clone())
+                    .or_default()
+                    .push(api.name().clone());
+            }
+        }
+    }
     let mut by_typename: HashMap<QualifiedName, ApiVec<FnPhase>> = HashMap::new();
     for api in apis.into_iter() {
         let tn = api.name().clone();
@@ -64,6 +93,9 @@ pub(crate) fn filter_apis_by_following_edges_from_allowlist(
             todos.extend(these_apis.iter().flat_map(|api| api.deps().cloned()));
             output.append(&mut these_apis);
         } // otherwise, probably an intrinsic e.g. uint32_t.
+        if let Some(methods) = methods_by_concrete_type.get(&todo) {
+            todos.extend(methods.iter().cloned());
+        }
         done.insert(todo);
     }
     output
@@ -0,0 +1,83 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Code to create functions which call `std::hash` for types which have
+//! a specialization of it. bindgen doesn't tell us about template
+//! specializations, so we rely on the user to tell us using `hashable!`.
+
+use autocxx_parser::IncludeCppConfig;
+use syn::{parse_quote, punctuated::Punctuated, token::Comma, FnArg, ReturnType};
+
+use crate::{
+    conversion::{
+        api::{Api, ApiName, CppVisibility, FuncToConvert, Provenance, TraitSynthesis},
+        apivec::ApiVec,
+    },
+    minisyn::minisynize_punctuated,
+    types::{make_ident, QualifiedName},
+};
+
+use super::{
+    fun::function_wrapper::{CppFunctionBody, CppFunctionKind},
+    pod::PodPhase,
+};
+
+pub(crate) fn create_hash_functions(
+    apis: ApiVec<PodPhase>,
+    config: &IncludeCppConfig,
+) -> ApiVec<PodPhase> {
+    apis.into_iter()
+        .flat_map(|api| -> Box<dyn Iterator<Item = Api<PodPhase>>> {
+            match &api {
+                Api::Struct { name, .. } if config.is_hashable_type(&name.name.to_cpp_name()) => {
+                    Box::new([create_hash_function(name.name.clone()), api].into_iter())
+                }
+                _ => Box::new(std::iter::once(api)),
+            }
+        })
+        .collect()
+}
+
+fn create_hash_function(ty_name: QualifiedName) -> Api<PodPhase> {
+    let typ = ty_name.to_type_path();
+    let inputs: Punctuated<FnArg, Comma> = parse_quote! {
+        arg0: __bindgen_marker_Reference < *const #typ >
+    };
+    let output: ReturnType = parse_quote! {
+        -> usize
+    };
+    let name = QualifiedName::new(
+        ty_name.get_namespace(),
+        make_ident(format!("{}_autocxx_hash", ty_name.get_final_item())),
+    );
+    Api::Function {
+        fun: Box::new(FuncToConvert {
+            ident: name.get_final_ident(),
+            doc_attrs: Vec::new(),
+            inputs: minisynize_punctuated(inputs),
+            output: output.into(),
+            vis: parse_quote! { pub },
+            virtualness: None,
+            cpp_vis: CppVisibility::Public,
+            special_member: None,
+            original_name: None,
+            self_ty: None,
+            synthesized_this_type: None,
+            synthetic_cpp: Some((
+                CppFunctionBody::StdHash(ty_name.clone()),
+                CppFunctionKind::Function,
+            )),
+            add_to_trait: Some(TraitSynthesis::Hash(ty_name)),
+            is_deleted: None,
+            provenance: Provenance::SynthesizedOther,
+            variadic: false,
+        }),
+        name: ApiName::new_from_qualified_name(name),
+        analysis: (),
+    }
+}
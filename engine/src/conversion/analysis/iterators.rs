@@ -0,0 +1,275 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Code to allow iteration over C++ types which have `begin()` and `end()`
+//! methods. For each such type we make a C++ struct holding the pair of
+//! C++ iterators, plus functions to create it and to step through it. The
+//! latter become implementations of `IntoIterator` and
+//! `autocxx::CppIteratorState` respectively.
+
+use autocxx_parser::IncludeCppConfig;
+use indexmap::map::IndexMap as HashMap;
+use indexmap::set::IndexSet as HashSet;
+use quote::quote;
+use syn::{parse_quote, punctuated::Punctuated, token::Comma, FnArg, ReturnType, Type, TypePtr};
+
+use crate::{
+    conversion::{
+        api::{Api, ApiName, CppVisibility, FuncToConvert, Provenance, TraitSynthesis},
+        apivec::ApiVec,
+    },
+    minisyn::minisynize_punctuated,
+    types::{make_ident, Namespace, QualifiedName},
+};
+
+use super::{
+    fun::function_wrapper::{CppFunctionBody, CppFunctionKind},
+    pod::PodPhase,
+};
+
+/// A `begin()` or `end()` method we've found.
+struct RangeMethod {
+    self_ty: QualifiedName,
+    mutable: bool,
+    is_begin: bool,
+    output: ReturnType,
+}
+
+pub(crate) fn create_iterator_functions(
+    apis: ApiVec<PodPhase>,
+    config: &IncludeCppConfig,
+) -> ApiVec<PodPhase> {
+    let structs: HashSet<_> = apis
+        .iter()
+        .filter_map(|api| match api {
+            Api::Struct { name, .. } => Some(name.name.clone()),
+            _ => None,
+        })
+        .collect();
+    let typedefs: HashSet<_> = apis
+        .iter()
+        .filter_map(|api| match api {
+            Api::Typedef { name, .. } => Some(name.name.clone()),
+            _ => None,
+        })
+        .collect();
+    // Keyed by the container type and whether we're iterating mutably;
+    // the value is the return type of `begin()`, and whether we've also
+    // found `end()`.
+    let mut ranges: HashMap<(QualifiedName, bool), (Option<ReturnType>, bool)> = HashMap::new();
+    for method in apis.iter().filter_map(|api| match api {
+        Api::Function { fun, .. } => find_range_method(fun),
+        _ => None,
+    }) {
+        let entry = ranges.entry((method.self_ty, method.mutable)).or_default();
+        if method.is_begin {
+            entry.0 = Some(method.output);
+        } else {
+            entry.1 = true;
+        }
+    }
+    let mut new_apis = ApiVec::new();
+    for ((self_ty, mutable), range) in ranges {
+        if let (Some(begin_output), true) = range {
+            if !structs.contains(&self_ty) {
+                continue;
+            }
+            let cpp_self_ty = self_ty.to_cpp_name();
+            if let Some(item_ty) = find_item_type(
+                &self_ty,
+                &begin_output,
+                &typedefs,
+                config.is_iterable_type(&cpp_self_ty),
+            ) {
+                new_apis.extend(create_iterator_apis(
+                    self_ty,
+                    &cpp_self_ty,
+                    mutable,
+                    item_ty,
+                ));
+            }
+        }
+    }
+    let mut apis = apis;
+    apis.append(&mut new_apis);
+    apis
+}
+
+/// Spot a public method called `begin` or `end` which takes no parameters.
+fn find_range_method(fun: &FuncToConvert) -> Option<RangeMethod> {
+    if !matches!(fun.cpp_vis, CppVisibility::Public)
+        || fun.is_deleted.is_some()
+        || fun.synthetic_cpp.is_some()
+        || fun.inputs.len() != 1
+    {
+        return None;
+    }
+    let is_begin = match fun
+        .original_name
+        .as_ref()?
+        .to_string_for_rust_name()
+        .as_str()
+    {
+        "begin" => true,
+        "end" => false,
+        _ => return None,
+    };
+    let (self_ty, mutable) = match &fun.inputs.first()?.0 {
+        FnArg::Typed(pt) => match (pt.pat.as_ref(), pt.ty.as_ref()) {
+            (
+                syn::Pat::Ident(pp),
+                Type::Ptr(TypePtr {
+                    elem, mutability, ..
+                }),
+            ) if pp.ident == "this" => match elem.as_ref() {
+                Type::Path(typ) => (QualifiedName::from_type_path(typ), mutability.is_some()),
+                _ => return None,
+            },
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(RangeMethod {
+        self_ty,
+        mutable,
+        is_begin,
+        output: fun.output.0.clone(),
+    })
+}
+
+/// Work out what type we'll get when we dereference the iterator. If
+/// `begin()` returns a pointer, that's easy. Otherwise we need the container
+/// to tell us using a `value_type` typedef, and the user to tell us (using
+/// `iterable!`) that the iterator yields references rather than values or
+/// proxies, which we couldn't point to.
+fn find_item_type(
+    self_ty: &QualifiedName,
+    begin_output: &ReturnType,
+    typedefs: &HashSet<QualifiedName>,
+    iterable: bool,
+) -> Option<Type> {
+    if let ReturnType::Type(_, ty) = begin_output {
+        if let Type::Ptr(TypePtr { elem, .. }) = ty.as_ref() {
+            return Some(elem.as_ref().clone());
+        }
+    }
+    if !iterable {
+        return None;
+    }
+    let value_type = QualifiedName::new(
+        self_ty.get_namespace(),
+        make_ident(format!("{}_value_type", self_ty.get_final_item())),
+    );
+    if typedefs.contains(&value_type) {
+        Some(Type::Path(value_type.to_type_path()))
+    } else {
+        None
+    }
+}
+
+/// Make the state needed to iterate over `self_ty`, which is known to C++
+/// as `cpp_self_ty`, and the functions to create and step through it.
+pub(crate) fn create_iterator_apis(
+    self_ty: QualifiedName,
+    cpp_self_ty: &str,
+    mutable: bool,
+    item_ty: Type,
+) -> impl Iterator<Item = Api<PodPhase>> {
+    let (suffix, const_token, mutability) = if mutable {
+        ("_mut", "", quote! { mut })
+    } else {
+        ("", "const ", quote! { const })
+    };
+    // The state type lives in the root namespace, because that's where
+    // we emit typedefs, so fold the container's namespace into its name.
+    let state = QualifiedName::new(
+        &Namespace::new(),
+        make_ident(format!(
+            "{}_AutocxxIter{suffix}",
+            self_ty.segment_iter().collect::<Vec<_>>().join("_"),
+        )),
+    );
+    let cpp_definition = format!(
+        "struct {{ decltype(static_cast<{const_token}{cpp_self_ty}*>(nullptr)->begin()) cur; decltype(static_cast<{const_token}{cpp_self_ty}*>(nullptr)->end()) end; }}"
+    );
+    let state_path = state.to_type_path();
+    let next_fn = QualifiedName::new(
+        &Namespace::new(),
+        make_ident(format!("{}_next", state.get_final_item())),
+    );
+    let next_inputs: Punctuated<FnArg, Comma> = parse_quote! {
+        this: *mut #state_path
+    };
+    let next_api = make_function(
+        next_fn.clone(),
+        next_inputs,
+        parse_quote! { -> *const #item_ty },
+        Some(state.clone()),
+        CppFunctionBody::IteratorNext,
+        TraitSynthesis::IteratorState(state.clone()),
+    );
+    let self_ty_path = self_ty.to_type_path();
+    let begin_inputs: Punctuated<FnArg, Comma> = parse_quote! {
+        arg0: __bindgen_marker_Reference < * #mutability #self_ty_path >
+    };
+    let begin_name = QualifiedName::new(
+        self_ty.get_namespace(),
+        make_ident(format!("{}_autocxx_iter{suffix}", self_ty.get_final_item())),
+    );
+    let begin_api = make_function(
+        begin_name,
+        begin_inputs,
+        parse_quote! { -> root::std::unique_ptr < #state_path > },
+        None,
+        CppFunctionBody::IteratorBegin(state.clone()),
+        TraitSynthesis::IntoIterator {
+            container: self_ty,
+            state: state.clone(),
+            next_fn,
+            mutable,
+        },
+    );
+    let state_api = Api::ConcreteType {
+        name: ApiName::new_from_qualified_name(state),
+        rs_definition: None,
+        cpp_definition,
+    };
+    [state_api, next_api, begin_api].into_iter()
+}
+
+fn make_function(
+    name: QualifiedName,
+    inputs: Punctuated<FnArg, Comma>,
+    output: ReturnType,
+    self_ty: Option<QualifiedName>,
+    body: CppFunctionBody,
+    synthesis: TraitSynthesis,
+) -> Api<PodPhase> {
+    Api::Function {
+        fun: Box::new(FuncToConvert {
+            ident: name.get_final_ident(),
+            doc_attrs: Vec::new(),
+            inputs: minisynize_punctuated(inputs),
+            output: output.into(),
+            vis: parse_quote! { pub },
+            virtualness: None,
+            cpp_vis: CppVisibility::Public,
+            special_member: None,
+            original_name: None,
+            self_ty,
+            synthesized_this_type: None,
+            synthetic_cpp: Some((body, CppFunctionKind::Function)),
+            add_to_trait: Some(synthesis),
+            is_deleted: None,
+            provenance: Provenance::SynthesizedOther,
+            variadic: false,
+        }),
+        name: ApiName::new_from_qualified_name(name),
+        analysis: (),
+    }
+}
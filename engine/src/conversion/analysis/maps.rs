@@ -0,0 +1,281 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Code to give each `std::map` or `std::unordered_map` which we find in
+//! a function signature some methods, so it can be used from Rust as an
+//! associative container. Each such map becomes a concrete type, just as
+//! it would for any other template, so we make that type here rather than
+//! waiting for the function analysis to do it. We then make functions to
+//! look things up in the map, to change it, and to iterate over it, each
+//! entry being a further concrete type with methods to get its key and value.
+
+use autocxx_parser::IncludeCppConfig;
+use indexmap::map::IndexMap as HashMap;
+use syn::{
+    parse_quote, punctuated::Punctuated, token::Comma, FnArg, GenericArgument, PathArguments,
+    ReturnType, Type, TypePath, TypePtr, TypeReference,
+};
+
+use crate::{
+    conversion::{
+        api::{Api, ApiName, CppVisibility, FuncToConvert, Provenance},
+        apivec::ApiVec,
+    },
+    minisyn::minisynize_punctuated,
+    types::{make_ident, Namespace, QualifiedName},
+};
+
+use super::{
+    fun::function_wrapper::{CppFunctionBody, CppFunctionKind},
+    iterators::create_iterator_apis,
+    pod::PodPhase,
+    type_converter::{add_analysis, TypeConversionContext, TypeConverter},
+};
+
+/// A map we've found, and the types of its keys and values.
+struct MapDetails {
+    cpp_definition: String,
+    key_ty: Type,
+    value_ty: Type,
+}
+
+pub(crate) fn create_map_functions(
+    apis: ApiVec<PodPhase>,
+    config: &IncludeCppConfig,
+) -> ApiVec<PodPhase> {
+    let mut cpp_definitions: HashMap<QualifiedName, String> = apis
+        .iter()
+        .filter_map(|api| match api {
+            Api::ConcreteType { cpp_definition, .. } => {
+                Some((api.name().clone(), cpp_definition.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+    let mut maps_found = Vec::new();
+    for api in apis.iter() {
+        let ns = api.name().get_namespace();
+        match api {
+            Api::Function { fun, .. } => {
+                for input in fun.inputs.iter() {
+                    if let FnArg::Typed(pt) = &input.0 {
+                        find_maps(&pt.ty, ns, &mut maps_found);
+                    }
+                }
+                if let ReturnType::Type(_, ty) = &fun.output.0 {
+                    find_maps(ty, ns, &mut maps_found);
+                }
+            }
+            // Earlier analysis may have already made concrete types for
+            // maps, for instance if they're the target of a typedef.
+            Api::ConcreteType {
+                rs_definition: Some(ty),
+                ..
+            } => find_maps(ty, ns, &mut maps_found),
+            _ => {}
+        }
+    }
+    let mut type_converter = TypeConverter::new(config, &apis);
+    let mut maps: HashMap<QualifiedName, MapDetails> = HashMap::new();
+    let mut new_apis = ApiVec::new();
+    for (map_ty, ns) in maps_found {
+        let (key_ty, value_ty) = match get_key_and_value_types(&map_ty) {
+            Some(types) => types,
+            None => continue,
+        };
+        // If we can't make a concrete type for this map, neither will
+        // the function analysis, so there's nothing to do.
+        let annotated = match type_converter.convert_type(
+            Type::Path(map_ty),
+            &ns,
+            &TypeConversionContext::WithinReference,
+        ) {
+            Ok(annotated) => annotated,
+            Err(_) => continue,
+        };
+        for concrete_api in annotated.extra_apis.into_iter() {
+            if let Api::ConcreteType { cpp_definition, .. } = &concrete_api {
+                cpp_definitions.insert(concrete_api.name().clone(), cpp_definition.clone());
+            }
+            new_apis.push(add_analysis(concrete_api));
+        }
+        let name = match &annotated.ty {
+            Type::Path(typ) => QualifiedName::from_type_path(typ),
+            _ => continue,
+        };
+        if let Some(cpp_definition) = cpp_definitions.get(&name) {
+            maps.entry(name).or_insert_with(|| MapDetails {
+                cpp_definition: cpp_definition.clone(),
+                key_ty,
+                value_ty,
+            });
+        }
+    }
+    for (name, details) in maps {
+        new_apis.extend(create_map_apis(name, details));
+    }
+    let mut apis = apis;
+    apis.append(&mut new_apis);
+    apis
+}
+
+/// Find any maps within this type, including as template arguments
+/// of other types.
+fn find_maps(ty: &Type, ns: &Namespace, maps_found: &mut Vec<(TypePath, Namespace)>) {
+    match ty {
+        Type::Path(typ) => {
+            if is_map(typ) {
+                maps_found.push((typ.clone(), ns.clone()));
+            }
+            for seg in &typ.path.segments {
+                if let PathArguments::AngleBracketed(ab) = &seg.arguments {
+                    for arg in &ab.args {
+                        if let GenericArgument::Type(ty) = arg {
+                            find_maps(ty, ns, maps_found);
+                        }
+                    }
+                }
+            }
+        }
+        Type::Ptr(TypePtr { elem, .. }) | Type::Reference(TypeReference { elem, .. }) => {
+            find_maps(elem, ns, maps_found)
+        }
+        _ => {}
+    }
+}
+
+fn is_map(typ: &TypePath) -> bool {
+    let tn = QualifiedName::from_type_path(typ);
+    tn.ns_segment_iter().next() == Some("std")
+        && matches!(tn.get_final_item(), "map" | "unordered_map")
+}
+
+/// bindgen may tell us about further template arguments, such as the
+/// comparator and allocator, but we only care about the first two.
+fn get_key_and_value_types(typ: &TypePath) -> Option<(Type, Type)> {
+    let mut types = match &typ.path.segments.last()?.arguments {
+        PathArguments::AngleBracketed(ab) => ab.args.iter().filter_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty.clone()),
+            _ => None,
+        }),
+        _ => return None,
+    };
+    Some((types.next()?, types.next()?))
+}
+
+fn create_map_apis(map: QualifiedName, details: MapDetails) -> impl Iterator<Item = Api<PodPhase>> {
+    let MapDetails {
+        cpp_definition,
+        key_ty,
+        value_ty,
+    } = details;
+    let map_path = map.to_type_path();
+    let key_ref: Type = parse_quote! { __bindgen_marker_Reference < *const #key_ty > };
+    let value_ref: Type = parse_quote! { __bindgen_marker_Reference < *const #value_ty > };
+    let entry = QualifiedName::new(
+        &Namespace::new(),
+        make_ident(format!("{}_AutocxxEntry", map.get_final_item())),
+    );
+    let entry_path = entry.to_type_path();
+    let entry_api = Api::ConcreteType {
+        name: ApiName::new_from_qualified_name(entry.clone()),
+        rs_definition: None,
+        cpp_definition: format!("{cpp_definition}::value_type"),
+    };
+    let methods = [
+        make_method(
+            &map,
+            "len",
+            parse_quote! { this: *const #map_path },
+            parse_quote! { -> usize },
+            CppFunctionBody::MapLen,
+        ),
+        make_method(
+            &map,
+            "get",
+            parse_quote! { this: *const #map_path, key: #key_ref },
+            parse_quote! { -> *const #value_ty },
+            CppFunctionBody::MapGet,
+        ),
+        make_method(
+            &map,
+            "contains_key",
+            parse_quote! { this: *const #map_path, key: #key_ref },
+            parse_quote! { -> bool },
+            CppFunctionBody::MapContainsKey,
+        ),
+        make_method(
+            &map,
+            "insert",
+            parse_quote! { this: *mut #map_path, key: #key_ty, value: #value_ty },
+            parse_quote! { -> bool },
+            CppFunctionBody::MapInsert,
+        ),
+        make_method(
+            &map,
+            "remove",
+            parse_quote! { this: *mut #map_path, key: #key_ref },
+            parse_quote! { -> bool },
+            CppFunctionBody::MapRemove,
+        ),
+        make_method(
+            &entry,
+            "key",
+            parse_quote! { this: *const #entry_path },
+            parse_quote! { -> #key_ref },
+            CppFunctionBody::MapEntryKey,
+        ),
+        make_method(
+            &entry,
+            "value",
+            parse_quote! { this: *const #entry_path },
+            parse_quote! { -> #value_ref },
+            CppFunctionBody::MapEntryValue,
+        ),
+    ];
+    let iterator_apis =
+        create_iterator_apis(map, &cpp_definition, false, Type::Path(entry_path.clone()));
+    std::iter::once(entry_api)
+        .chain(methods)
+        .chain(iterator_apis)
+}
+
+fn make_method(
+    self_ty: &QualifiedName,
+    method_name: &str,
+    inputs: Punctuated<FnArg, Comma>,
+    output: ReturnType,
+    body: CppFunctionBody,
+) -> Api<PodPhase> {
+    let name = QualifiedName::new(
+        &Namespace::new(),
+        make_ident(format!("{}_{method_name}", self_ty.get_final_item())),
+    );
+    Api::Function {
+        fun: Box::new(FuncToConvert {
+            ident: make_ident(method_name),
+            doc_attrs: Vec::new(),
+            inputs: minisynize_punctuated(inputs),
+            output: output.into(),
+            vis: parse_quote! { pub },
+            virtualness: None,
+            cpp_vis: CppVisibility::Public,
+            special_member: None,
+            original_name: None,
+            self_ty: Some(self_ty.clone()),
+            synthesized_this_type: None,
+            synthetic_cpp: Some((body, CppFunctionKind::Function)),
+            add_to_trait: None,
+            is_deleted: None,
+            provenance: Provenance::SynthesizedOther,
+            variadic: false,
+        }),
+        name: ApiName::new_from_qualified_name(name),
+        analysis: (),
+    }
+}
@@ -9,19 +9,25 @@
 pub(crate) mod abstract_types;
 pub(crate) mod allocators;
 pub(crate) mod casts;
+pub(crate) mod concrete_methods;
 pub(crate) mod constructor_deps;
 pub(crate) mod ctypes;
 pub(crate) mod deps;
 mod depth_first;
 mod doc_label;
+pub(crate) mod field_accessors;
 pub(crate) mod fun;
 pub(crate) mod gc;
+pub(crate) mod hashes;
+pub(crate) mod iterators;
+pub(crate) mod maps;
 mod name_check;
 pub(crate) mod pod; // hey, that rhymes
 pub(crate) mod remove_ignored;
 mod replace_hopeless_typedef_targets;
 pub(crate) mod tdef;
 mod type_converter;
+pub(crate) mod variadics;
 
 pub(crate) use name_check::check_names;
 pub(crate) use replace_hopeless_typedef_targets::replace_hopeless_typedef_targets;
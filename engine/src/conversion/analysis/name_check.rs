@@ -64,6 +64,8 @@ pub(crate) fn check_names(apis: ApiVec<FnPhase>) -> ApiVec<FnPhase> {
             Ok(Box::new(std::iter::once(api)))
         }
         Api::ConcreteType { .. }
+        | Api::TupleStruct { .. }
+        | Api::VariantStruct { .. }
         | Api::CType { .. }
         | Api::StringConstructor { .. }
         | Api::RustType { .. }
@@ -7,6 +7,7 @@
 // except according to those terms.
 
 use crate::conversion::apivec::ApiVec;
+use crate::conversion::type_helpers::type_is_reference;
 use crate::{conversion::ConvertErrorFromCpp, known_types::known_types};
 use crate::{
     conversion::{
@@ -17,7 +18,7 @@ use crate::{
 };
 use autocxx_parser::IncludeCppConfig;
 use std::collections::HashMap;
-use syn::{ItemStruct, Type};
+use syn::{ItemStruct, Type, TypeArray};
 
 #[derive(Clone)]
 enum PodState {
@@ -184,6 +185,12 @@ impl ByValueChecker {
                 format!("Type {tyname} could not be POD because it has virtual functions.");
             field_safety_problem = PodState::UnsafeToBePod(reason);
         }
+        if Self::has_rvalue_reference_field(def) {
+            let reason = format!(
+                "Type {tyname} could not be POD because it has rvalue reference (&&) fields."
+            );
+            field_safety_problem = PodState::UnsafeToBePod(reason);
+        }
         let mut my_details = StructDetails::new(field_safety_problem);
         my_details.dependent_structs = fieldlist;
         self.results.insert(tyname, my_details);
@@ -256,11 +263,15 @@ impl ByValueChecker {
     fn get_field_types(def: &ItemStruct) -> Vec<QualifiedName> {
         let mut results = Vec::new();
         for f in &def.fields {
-            let fty = &f.ty;
+            let mut fty = &f.ty;
+            // An array is POD if its elements are.
+            while let Type::Array(TypeArray { elem, .. }) = fty {
+                fty = elem.as_ref();
+            }
             if let Type::Path(p) = fty {
                 results.push(QualifiedName::from_type_path(p));
             }
-            // TODO handle anything else which bindgen might spit out, e.g. arrays?
+            // TODO handle anything else which bindgen might spit out?
         }
         results
     }
@@ -273,6 +284,10 @@ impl ByValueChecker {
         }
         false
     }
+
+    fn has_rvalue_reference_field(def: &ItemStruct) -> bool {
+        def.fields.iter().any(|f| type_is_reference(&f.ty, true))
+    }
 }
 
 #[cfg(test)]
@@ -358,4 +373,48 @@ mod tests {
         bvc.ingest_struct(&t, &Namespace::new());
         assert!(bvc.satisfy_requests(vec![t_id]).is_err());
     }
+
+    #[test]
+    fn test_with_array_of_primitives() {
+        let mut bvc = ByValueChecker::new();
+        let t: ItemStruct = parse_quote! {
+            struct Bar {
+                a: [[f32; 4usize]; 4usize],
+                b: i64,
+            }
+        };
+        let t_id = ty_from_ident(&t.ident);
+        bvc.ingest_struct(&t, &Namespace::new());
+        bvc.satisfy_requests(vec![t_id.clone()]).unwrap();
+        assert!(bvc.is_pod(&t_id));
+    }
+
+    #[test]
+    fn test_with_array_of_cxxstring() {
+        let mut bvc = ByValueChecker::new();
+        let t: ItemStruct = parse_quote! {
+            struct Bar {
+                a: [CxxString; 2usize],
+                b: i64,
+            }
+        };
+        let t_id = ty_from_ident(&t.ident);
+        bvc.ingest_struct(&t, &Namespace::new());
+        assert!(bvc.satisfy_requests(vec![t_id]).is_err());
+    }
+
+    #[test]
+    fn test_with_rvalue_reference() {
+        let mut bvc = ByValueChecker::new();
+        let t: ItemStruct = parse_quote! {
+            struct Bar {
+                a: __bindgen_marker_RValueReference<*mut i64>,
+                b: i64,
+            }
+        };
+        let t_id = ty_from_ident(&t.ident);
+        bvc.ingest_struct(&t, &Namespace::new());
+        assert!(bvc.satisfy_requests(vec![t_id.clone()]).is_err());
+        assert!(!bvc.is_pod(&t_id));
+    }
 }
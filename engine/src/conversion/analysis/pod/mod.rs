@@ -13,7 +13,7 @@ use indexmap::set::IndexSet as HashSet;
 
 use autocxx_parser::IncludeCppConfig;
 use byvalue_checker::ByValueChecker;
-use syn::{ItemStruct, Type, Visibility};
+use syn::{ItemStruct, Type, TypeArray, Visibility};
 
 use crate::{
     conversion::{
@@ -167,13 +167,6 @@ fn analyze_struct(
         TypeKind::Opaque
     } else if byvalue_checker.is_pod(&name.name) {
         // It's POD so any errors encountered parsing its fields are important.
-        // Let's not allow anything to be POD if it's got rvalue reference fields.
-        if details.has_rvalue_reference_fields {
-            return Err(ConvertErrorWithContext(
-                ConvertErrorFromCpp::RValueReferenceField,
-                Some(ErrorContext::new_for_item(id)),
-            ));
-        }
         if let Some(err) = field_conversion_errors.into_iter().next() {
             return Err(ConvertErrorWithContext(
                 err,
@@ -182,6 +175,8 @@ fn analyze_struct(
         }
         TypeKind::Pod
     } else {
+        // This includes types with rvalue reference fields, which can never
+        // be POD. Rust sees them as opaque, so has no need of their fields.
         TypeKind::NonPod
     };
     let castable_bases = bases
@@ -245,12 +240,15 @@ fn get_struct_field_types(
                     .unwrap_or(false)
                 {
                     field_deps.extend(r.types_encountered);
-                    if let Type::Path(typ) = &r.ty {
-                        // Later analyses need to know about the field
-                        // types where we need full definitions, as opposed
-                        // to just declarations. That means just the outermost
-                        // type path.
-                        // TODO: consider arrays.
+                    // Later analyses need to know about the field
+                    // types where we need full definitions, as opposed
+                    // to just declarations. That means just the outermost
+                    // type path, or the element type of an array.
+                    let mut field_ty = &r.ty;
+                    while let Type::Array(TypeArray { elem, .. }) = field_ty {
+                        field_ty = elem.as_ref();
+                    }
+                    if let Type::Path(typ) = field_ty {
                         field_definition_deps.insert(QualifiedName::from_type_path(typ));
                     }
                     field_info.push(FieldInfo {
@@ -11,7 +11,10 @@ use crate::{
         api::{AnalysisPhase, Api, ApiName, NullPhase, TypedefKind, UnanalyzedApi},
         apivec::ApiVec,
         codegen_cpp::type_to_cpp::CppNameMap,
-        type_helpers::{unwrap_has_opaque, unwrap_has_unused_template_param, unwrap_reference},
+        type_helpers::{
+            unwrap_function_pointer, unwrap_has_opaque, unwrap_has_unused_template_param,
+            unwrap_reference,
+        },
         ConvertErrorFromCpp,
     },
     known_types::{known_types, CxxGenericType},
@@ -25,7 +28,7 @@ use proc_macro2::Ident;
 use quote::ToTokens;
 use syn::{
     parse_quote, punctuated::Punctuated, token::Comma, GenericArgument, PathArguments, PathSegment,
-    Type, TypePath, TypePtr,
+    ReturnType, Type, TypeBareFn, TypePath, TypePtr,
 };
 
 use super::tdef::TypedefAnalysis;
@@ -39,6 +42,35 @@ pub(crate) enum TypeKind {
     Reference,
     RValueReference,
     MutableReference,
+    /// A C++ view of bytes, such as `std::string_view`, represented in Rust
+    /// as `&[u8]`.
+    ByteView,
+    /// A `std::optional`, represented in Rust as an `Option`.
+    Optional,
+    /// A `std::span`, represented in Rust as a slice.
+    Span,
+    /// A `std::pair` or `std::tuple`, represented in the bridge as a
+    /// struct of which we know the layout, and to the user as this
+    /// Rust tuple.
+    Tuple(Box<crate::minisyn::Type>),
+    /// A `std::variant`, represented in the bridge as a struct of which
+    /// we know the layout, and to the user as an enum.
+    Variant(VariantDetails),
+    /// A reference to a C array, represented in Rust as a reference to
+    /// a Rust array, which cxx passes as a `std::array`.
+    ArrayReference,
+    /// A C function pointer, represented in the bridge as a `void*` since
+    /// cxx can't represent it, and to the user as this
+    /// `Option<unsafe extern "C" fn(...)>`.
+    FunctionPointer(Box<crate::minisyn::Type>),
+}
+
+/// The enum we'll make to represent a `std::variant`, and the name and
+/// type of each of its variants.
+#[derive(Debug)]
+pub(crate) struct VariantDetails {
+    pub(crate) enum_name: crate::minisyn::Ident,
+    pub(crate) alternatives: Vec<(crate::minisyn::Ident, crate::minisyn::Type)>,
 }
 
 /// Results of some type conversion, annotated with a list of every type encountered,
@@ -94,6 +126,12 @@ impl TypeConversionContext {
     fn allow_instantiation_of_forward_declaration(&self) -> bool {
         matches!(self, Self::WithinReference)
     }
+    /// Whether we can represent C++ types which are passed by value
+    /// using Rust types which have a different layout, relying on
+    /// conversions in the generated C++ wrapper functions.
+    fn allow_conversion_to_different_layout(&self) -> bool {
+        matches!(self, Self::OuterType)
+    }
     fn allowed_generic_type(&self, ident: &Ident) -> bool {
         !matches!(self,
             Self::WithinStructField { struct_type_params }
@@ -113,6 +151,7 @@ pub(crate) struct TypeConverter<'a> {
     types_found: HashSet<QualifiedName>,
     typedefs: HashMap<QualifiedName, Type>,
     concrete_templates: HashMap<String, QualifiedName>,
+    tuple_structs: HashMap<String, QualifiedName>,
     forward_declarations: HashSet<QualifiedName>,
     ignored_types: HashSet<QualifiedName>,
     config: &'a IncludeCppConfig,
@@ -128,6 +167,7 @@ impl<'a> TypeConverter<'a> {
             types_found: find_types(apis),
             typedefs: Self::find_typedefs(apis),
             concrete_templates: Self::find_concrete_templates(apis),
+            tuple_structs: Self::find_tuple_structs(apis),
             forward_declarations: Self::find_incomplete_types(apis),
             ignored_types: Self::find_ignored_types(apis),
             config,
@@ -135,6 +175,12 @@ impl<'a> TypeConverter<'a> {
         }
     }
 
+    /// Whether we know only of a forward declaration of this type, so
+    /// can't hold it in a `UniquePtr` or similar.
+    pub(crate) fn is_forward_declaration(&self, qn: &QualifiedName) -> bool {
+        self.forward_declarations.contains(qn)
+    }
+
     pub(crate) fn convert_boxed_type(
         &mut self,
         ty: Box<Type>,
@@ -200,11 +246,29 @@ impl<'a> TypeConverter<'a> {
         } else if let Some(ptr) = unwrap_reference(&typ, false) {
             // LValue reference
             let mutability = ptr.mutability;
+            // A const reference to a view can be treated just like the view
+            // itself, since we'll make a temporary view to bind to it.
+            if mutability.is_none()
+                && ctx.allow_conversion_to_different_layout()
+                && self.is_byte_view(&ptr.elem)
+            {
+                return Ok(Self::byte_view());
+            }
             let elem = self.convert_boxed_type(
                 ptr.elem.clone(),
                 ns,
                 &TypeConversionContext::WithinReference,
             )?;
+            // Arrays are Unpin, so a mutable reference needn't be pinned.
+            if matches!(*elem.ty, Type::Array(..)) {
+                let mut outer = elem.map(|elem| {
+                    Type::Reference(parse_quote! {
+                        & #mutability #elem
+                    })
+                });
+                outer.kind = TypeKind::ArrayReference;
+                return Ok(outer);
+            }
             // TODO - in the future, we should check if this is a rust::Str and throw
             // a wobbler if not. rust::Str should only be seen _by value_ in C++
             // headers; it manifests as &str in Rust but on the C++ side it must
@@ -223,9 +287,20 @@ impl<'a> TypeConverter<'a> {
                 TypeKind::Reference
             };
             Ok(outer)
+        } else if let Some(bare_fn) = unwrap_function_pointer(&typ) {
+            if ctx.allow_conversion_to_different_layout() {
+                self.convert_function_pointer(bare_fn.clone(), ns)
+            } else {
+                Err(ConvertErrorFromCpp::UnsupportedType(
+                    typ.to_token_stream().to_string(),
+                ))
+            }
         } else if let Some(ptr) = unwrap_reference(&typ, true) {
             // RValue reference
             Self::ensure_pointee_is_valid(ptr)?;
+            if ctx.allow_conversion_to_different_layout() && self.is_byte_view(&ptr.elem) {
+                return Ok(Self::byte_view());
+            }
             let innerty = self.convert_boxed_type(
                 ptr.elem.clone(),
                 ns,
@@ -318,6 +393,17 @@ impl<'a> TypeConverter<'a> {
         let (mut typ, tn) = match self.resolve_typedef(&original_tn)? {
             None => (typ, original_tn),
             Some(Type::Path(resolved_tp)) => {
+                // We can't represent a typedef of a C function pointer, so
+                // use the function pointer type directly.
+                if let Some(bare_fn) = unwrap_function_pointer(resolved_tp).cloned() {
+                    return if ctx.allow_conversion_to_different_layout() {
+                        self.convert_function_pointer(bare_fn, ns)
+                    } else {
+                        Err(ConvertErrorFromCpp::UnsupportedType(
+                            original_tn.to_cpp_name(),
+                        ))
+                    };
+                }
                 let resolved_tn = QualifiedName::from_type_path(resolved_tp);
                 deps.insert(resolved_tn.clone());
                 (resolved_tp.clone(), resolved_tn)
@@ -340,6 +426,42 @@ impl<'a> TypeConverter<'a> {
             }
         };
 
+        // C++ views of bytes, such as `std::string_view`, become byte slices.
+        // A slice doesn't have the layout of the view, so anywhere else we
+        // can't represent them at all.
+        if known_types().is_byte_view(&tn) {
+            return if ctx.allow_conversion_to_different_layout() {
+                Ok(Self::byte_view())
+            } else {
+                Err(ConvertErrorFromCpp::UnsupportedType(tn.to_cpp_name()))
+            };
+        }
+
+        if ctx.allow_conversion_to_different_layout() {
+            // A `std::span<const char>` can be made from a `std::string_view`,
+            // so is treated just like one.
+            if Self::is_span_of_chars(&tn, &typ) {
+                return Ok(Self::byte_view());
+            }
+            if tn == QualifiedName::new_from_cpp_name("std::optional") {
+                return self.convert_wrapped_type_arg(&typ, ns, TypeKind::Optional);
+            }
+            // bindgen doesn't tell us whether the elements of a span are
+            // const. We assume so; the function analysis makes the slice
+            // mutable if the user tells us otherwise.
+            if tn == QualifiedName::new_from_cpp_name("std::span") {
+                return self.convert_wrapped_type_arg(&typ, ns, TypeKind::Span);
+            }
+            if tn == QualifiedName::new_from_cpp_name("std::pair")
+                || tn == QualifiedName::new_from_cpp_name("std::tuple")
+            {
+                return self.convert_tuple(&typ, &tn, ns);
+            }
+            if tn == QualifiedName::new_from_cpp_name("std::variant") {
+                return self.convert_variant(&typ, &tn, ns);
+            }
+        }
+
         // Now let's see if it's a known type.
         // (We may entirely reject some types at this point too.)
         let mut typ = match known_types().consider_substitution(&tn) {
@@ -439,6 +561,312 @@ impl<'a> TypeConverter<'a> {
         Ok(Annotated::new(Type::Path(typ), deps, extra_apis, kind))
     }
 
+    /// Convert the type argument of a `std::optional<T>` or `std::span<T>`,
+    /// and wrap it into the Rust type we'll use to represent the whole
+    /// thing. Depending on `T`, the function analysis will later decide how
+    /// to pass it across the bridge.
+    fn convert_wrapped_type_arg(
+        &mut self,
+        typ: &TypePath,
+        ns: &Namespace,
+        kind: TypeKind,
+    ) -> Result<Annotated<Type>, ConvertErrorFromCpp> {
+        let inner = match typ.path.segments.last().map(|seg| &seg.arguments) {
+            // A `std::span<T, N>` of fixed extent can't be made from an
+            // arbitrary slice.
+            Some(PathArguments::AngleBracketed(ab)) if ab.args.len() > 1 => {
+                return Err(ConvertErrorFromCpp::UnsupportedType(
+                    typ.to_token_stream().to_string(),
+                ))
+            }
+            Some(PathArguments::AngleBracketed(ab)) => ab.args.first(),
+            _ => None,
+        };
+        let inner = match inner {
+            Some(GenericArgument::Type(inner)) => inner.clone(),
+            _ => {
+                return Err(ConvertErrorFromCpp::TemplatedTypeContainingNonPathArg(
+                    QualifiedName::from_type_path(typ),
+                ))
+            }
+        };
+        let inner = self.convert_type(inner, ns, &TypeConversionContext::WithinContainer)?;
+        match (&inner.ty, &inner.kind) {
+            (Type::Path(_), TypeKind::Regular) => {
+                let inner_ty = &inner.ty;
+                let ty = match kind {
+                    TypeKind::Span => parse_quote! { &[#inner_ty] },
+                    _ => parse_quote! { Option<#inner_ty> },
+                };
+                Ok(Annotated::new(
+                    ty,
+                    inner.types_encountered,
+                    inner.extra_apis,
+                    kind,
+                ))
+            }
+            _ => Err(ConvertErrorFromCpp::UnsupportedType(
+                typ.to_token_stream().to_string(),
+            )),
+        }
+    }
+
+    /// Convert the parameter and return types of a C function pointer,
+    /// which we'll pass through cxx as a `void*`.
+    fn convert_function_pointer(
+        &mut self,
+        mut bare_fn: TypeBareFn,
+        ns: &Namespace,
+    ) -> Result<Annotated<Type>, ConvertErrorFromCpp> {
+        let mut deps = HashSet::new();
+        let mut extra_apis = ApiVec::new();
+        let mut convert = |this: &mut Self, ty: Type| {
+            let mut annotated =
+                this.convert_type(ty, ns, &TypeConversionContext::WithinReference)?;
+            match annotated.kind {
+                TypeKind::Regular
+                | TypeKind::Pointer
+                | TypeKind::Reference
+                | TypeKind::MutableReference => {}
+                _ => {
+                    return Err(ConvertErrorFromCpp::UnsupportedType(
+                        annotated.ty.to_token_stream().to_string(),
+                    ))
+                }
+            }
+            deps.extend(annotated.types_encountered);
+            extra_apis.append(&mut annotated.extra_apis);
+            Ok(annotated.ty)
+        };
+        for input in bare_fn.inputs.iter_mut() {
+            input.ty = convert(self, input.ty.clone())?;
+        }
+        if let ReturnType::Type(_, ty) = &mut bare_fn.output {
+            **ty = convert(self, ty.as_ref().clone())?;
+        }
+        let void_ptr = self.convert_type(
+            parse_quote! { *mut ::std::os::raw::c_void },
+            ns,
+            &TypeConversionContext::OuterType,
+        )?;
+        deps.extend(void_ptr.types_encountered);
+        let user_ty: Type = parse_quote! { Option<#bare_fn> };
+        Ok(Annotated::new(
+            void_ptr.ty,
+            deps,
+            extra_apis,
+            TypeKind::FunctionPointer(Box::new(user_ty.into())),
+        ))
+    }
+
+    /// Convert a `std::pair` or `std::tuple` into a Rust tuple. We can't
+    /// rely on the layout of either, so we make a struct with the same
+    /// elements which the C++ wrapper functions can convert to and from,
+    /// and which we can pass by value. The function analysis will later
+    /// check that each element is something we can pass by value too.
+    fn convert_tuple(
+        &mut self,
+        typ: &TypePath,
+        tn: &QualifiedName,
+        ns: &Namespace,
+    ) -> Result<Annotated<Type>, ConvertErrorFromCpp> {
+        let Annotated {
+            ty: elems,
+            types_encountered: mut deps,
+            mut extra_apis,
+            ..
+        } = self.convert_simple_type_args(typ, tn, ns)?;
+        let cpp_elems = elems
+            .iter()
+            .map(|elem| self.original_name_map.type_to_cpp(elem))
+            .collect::<Result<Vec<_>, _>>()?;
+        let cpp_definition = format!("{}<{}>", tn.to_cpp_name(), cpp_elems.join(", "));
+        let name = match self.tuple_structs.get(&cpp_definition) {
+            Some(name) => name.clone(),
+            None => {
+                let name = QualifiedName::new(
+                    &Namespace::new(),
+                    make_ident(format!(
+                        "{}_AutocxxTuple",
+                        Self::sanitize_for_ident(&cpp_definition)
+                    )),
+                );
+                extra_apis.push(UnanalyzedApi::TupleStruct {
+                    name: ApiName::new_from_qualified_name(name.clone()),
+                    elems: elems.iter().cloned().map(Into::into).collect(),
+                    cpp_definition: cpp_definition.clone(),
+                });
+                self.tuple_structs.insert(cpp_definition, name.clone());
+                name
+            }
+        };
+        deps.insert(name.clone());
+        Ok(Annotated::new(
+            Type::Path(name.to_type_path()),
+            deps,
+            extra_apis,
+            TypeKind::Tuple(Box::new(parse_quote! { (#(#elems,)*) })),
+        ))
+    }
+
+    /// Convert a `std::variant` into a struct which we can pass by value,
+    /// and which will appear in Rust as an enum with a variant for each
+    /// alternative. Only the function analysis knows which alternatives
+    /// we can hold by value, so it will make the struct.
+    fn convert_variant(
+        &mut self,
+        typ: &TypePath,
+        tn: &QualifiedName,
+        ns: &Namespace,
+    ) -> Result<Annotated<Type>, ConvertErrorFromCpp> {
+        let Annotated {
+            ty: types,
+            types_encountered: mut deps,
+            extra_apis,
+            ..
+        } = self.convert_simple_type_args(typ, tn, ns)?;
+        let cpp_types = types
+            .iter()
+            .map(|ty| self.original_name_map.type_to_cpp(ty))
+            .collect::<Result<Vec<_>, _>>()?;
+        let base_name =
+            Self::sanitize_for_ident(&format!("{}<{}>", tn.to_cpp_name(), cpp_types.join(", ")));
+        let mut alternatives: Vec<(crate::minisyn::Ident, crate::minisyn::Type)> = Vec::new();
+        for ty in types {
+            // Each variant of the enum is named after the type it holds,
+            // so we can't cope with two alternatives whose types share
+            // a name.
+            match Self::enum_variant_name(&ty) {
+                Some(name) if !alternatives.iter().any(|(existing, _)| existing == &name) => {
+                    alternatives.push((make_ident(name), ty.into()))
+                }
+                _ => {
+                    return Err(ConvertErrorFromCpp::UnsupportedType(
+                        typ.to_token_stream().to_string(),
+                    ))
+                }
+            }
+        }
+        let name = QualifiedName::new(
+            &Namespace::new(),
+            make_ident(format!("{base_name}_AutocxxVariantStruct")),
+        );
+        deps.insert(name.clone());
+        Ok(Annotated::new(
+            Type::Path(name.to_type_path()),
+            deps,
+            extra_apis,
+            TypeKind::Variant(VariantDetails {
+                enum_name: make_ident(format!("{base_name}_AutocxxVariant")),
+                alternatives,
+            }),
+        ))
+    }
+
+    /// Make a name for the enum variant which holds this type, e.g.
+    /// `CInt` for `c_int`.
+    fn enum_variant_name(ty: &Type) -> Option<String> {
+        let ident = match ty {
+            Type::Path(tp) => tp.path.segments.last()?.ident.to_string(),
+            _ => return None,
+        };
+        Some(
+            ident
+                .split('_')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                        None => String::new(),
+                    }
+                })
+                .join(""),
+        )
+    }
+
+    /// Convert the type arguments of a `std::pair`, `std::tuple` or
+    /// `std::variant`, each of which must be a simple type rather than
+    /// a pointer or reference.
+    fn convert_simple_type_args(
+        &mut self,
+        typ: &TypePath,
+        tn: &QualifiedName,
+        ns: &Namespace,
+    ) -> Result<Annotated<Vec<Type>>, ConvertErrorFromCpp> {
+        let args = match typ.path.segments.last().map(|seg| &seg.arguments) {
+            Some(PathArguments::AngleBracketed(ab)) if !ab.args.is_empty() => &ab.args,
+            _ => {
+                return Err(ConvertErrorFromCpp::TemplatedTypeContainingNonPathArg(
+                    tn.clone(),
+                ))
+            }
+        };
+        let mut types = Vec::new();
+        let mut deps = HashSet::new();
+        let mut extra_apis = ApiVec::new();
+        for arg in args {
+            let ty = match arg {
+                GenericArgument::Type(ty) => ty.clone(),
+                _ => {
+                    return Err(ConvertErrorFromCpp::TemplatedTypeContainingNonPathArg(
+                        tn.clone(),
+                    ))
+                }
+            };
+            let mut ty = self.convert_type(ty, ns, &TypeConversionContext::WithinContainer)?;
+            if !matches!((&ty.ty, &ty.kind), (Type::Path(_), TypeKind::Regular)) {
+                return Err(ConvertErrorFromCpp::UnsupportedType(
+                    typ.to_token_stream().to_string(),
+                ));
+            }
+            deps.extend(ty.types_encountered.drain(..));
+            extra_apis.append(&mut ty.extra_apis);
+            types.push(ty.ty);
+        }
+        Ok(Annotated::new(types, deps, extra_apis, TypeKind::Regular))
+    }
+
+    /// Whether this type is a C++ view of bytes, such as `std::string_view`,
+    /// once any typedefs are resolved.
+    fn is_byte_view(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Path(typ) => {
+                let tn = QualifiedName::from_type_path(typ);
+                match self.resolve_typedef(&tn) {
+                    Ok(Some(Type::Path(resolved))) => {
+                        known_types().is_byte_view(&QualifiedName::from_type_path(resolved))
+                    }
+                    _ => known_types().is_byte_view(&tn),
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether this is a `std::span` of `char`s, of dynamic extent.
+    fn is_span_of_chars(tn: &QualifiedName, typ: &TypePath) -> bool {
+        if tn != &QualifiedName::new_from_cpp_name("std::span") {
+            return false;
+        }
+        match typ.path.segments.last().map(|seg| &seg.arguments) {
+            Some(PathArguments::AngleBracketed(ab)) if ab.args.len() == 1 => matches!(
+                ab.args.first(),
+                Some(GenericArgument::Type(Type::Path(inner)))
+                    if inner.path.segments.last().is_some_and(|seg| seg.ident == "c_char")
+            ),
+            _ => false,
+        }
+    }
+
+    fn byte_view() -> Annotated<Type> {
+        Annotated::new(
+            parse_quote! { &[u8] },
+            HashSet::new(),
+            ApiVec::new(),
+            TypeKind::ByteView,
+        )
+    }
+
     fn get_generic_args(typ: &mut TypePath) -> Option<&mut PathSegment> {
         match typ.path.segments.last_mut() {
             Some(s) if !s.arguments.is_empty() => Some(s),
@@ -529,9 +957,8 @@ impl<'a> TypeConverter<'a> {
 
     fn ensure_pointee_is_valid(ptr: &TypePtr) -> Result<(), ConvertErrorFromCpp> {
         match *ptr.elem {
-            Type::Path(..) => Ok(()),
+            Type::Path(..) | Type::Ptr(..) => Ok(()),
             Type::Array(..) => Err(ConvertErrorFromCpp::InvalidArrayPointee),
-            Type::Ptr(..) => Err(ConvertErrorFromCpp::InvalidPointerPointee),
             _ => Err(ConvertErrorFromCpp::InvalidPointee(
                 ptr.elem.to_token_stream().to_string(),
             )),
@@ -553,14 +980,8 @@ impl<'a> TypeConverter<'a> {
             None => {
                 let synthetic_ident = format!(
                     "{}_AutocxxConcrete",
-                    cpp_definition.replace(|c: char| !(c.is_ascii_alphanumeric() || c == '_'), "_")
+                    Self::sanitize_for_ident(&cpp_definition)
                 );
-                // Remove runs of multiple _s. Trying to avoid a dependency on
-                // regex.
-                let synthetic_ident = synthetic_ident
-                    .split('_')
-                    .filter(|s| !s.is_empty())
-                    .join("_");
                 // Ensure we're not duplicating some existing concrete template name.
                 // If so, we'll invent a name which is guaranteed to be unique.
                 let synthetic_ident = match self
@@ -584,6 +1005,16 @@ impl<'a> TypeConverter<'a> {
         }
     }
 
+    /// Make a C++ type name into something we can use as part of an
+    /// identifier.
+    fn sanitize_for_ident(cpp_definition: &str) -> String {
+        let sanitized =
+            cpp_definition.replace(|c: char| !(c.is_ascii_alphanumeric() || c == '_'), "_");
+        // Remove runs of multiple _s. Trying to avoid a dependency on
+        // regex.
+        sanitized.split('_').filter(|s| !s.is_empty()).join("_")
+    }
+
     fn confirm_inner_type_is_acceptable_generic_payload(
         &self,
         path_args: &Punctuated<GenericArgument, Comma>,
@@ -679,6 +1110,17 @@ impl<'a> TypeConverter<'a> {
             .collect()
     }
 
+    fn find_tuple_structs<A: AnalysisPhase>(apis: &ApiVec<A>) -> HashMap<String, QualifiedName> {
+        apis.iter()
+            .filter_map(|api| match &api {
+                Api::TupleStruct { cpp_definition, .. } => {
+                    Some((cpp_definition.clone(), api.name().clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     fn find_incomplete_types<A: AnalysisPhase>(apis: &ApiVec<A>) -> HashSet<QualifiedName> {
         apis.iter()
             .filter_map(|api| match api {
@@ -720,6 +1162,24 @@ pub(crate) fn add_analysis<A: AnalysisPhase>(api: UnanalyzedApi) -> Api<A> {
             rs_definition,
             cpp_definition,
         },
+        Api::TupleStruct {
+            name,
+            elems,
+            cpp_definition,
+        } => Api::TupleStruct {
+            name,
+            elems,
+            cpp_definition,
+        },
+        Api::VariantStruct {
+            name,
+            enum_name,
+            alternatives,
+        } => Api::VariantStruct {
+            name,
+            enum_name,
+            alternatives,
+        },
         Api::IgnoredItem { name, err, ctx } => Api::IgnoredItem { name, err, ctx },
         _ => panic!("Function analysis created an unexpected type of extra API"),
     }
@@ -749,6 +1209,8 @@ pub(crate) fn find_types<A: AnalysisPhase>(apis: &ApiVec<A>) -> HashSet<Qualifie
             Api::ForwardDeclaration { .. }
             | Api::OpaqueTypedef { .. }
             | Api::ConcreteType { .. }
+            | Api::TupleStruct { .. }
+            | Api::VariantStruct { .. }
             | Api::Typedef { .. }
             | Api::Enum { .. }
             | Api::Struct { .. }
@@ -0,0 +1,137 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Code to replace variadic functions with the fixed-arity instantiations
+//! of them which the user asked for using `variadic!`. cxx can't call a
+//! variadic function, so each instantiation is a C++ wrapper function
+//! which takes the listed types and passes them on in place of the `...`.
+//! They're all named after the original function, so the usual overload
+//! naming gives them distinct Rust names.
+
+use autocxx_parser::IncludeCppConfig;
+use syn::{parse_quote, Type};
+
+use crate::{
+    conversion::{
+        api::{Api, ApiName, FuncToConvert},
+        apivec::ApiVec,
+        CppOriginalName,
+    },
+    minisyn::FnArg,
+    types::make_ident,
+};
+
+use super::{
+    fun::function_wrapper::{CppFunctionBody, CppFunctionKind},
+    pod::PodPhase,
+};
+
+/// The C types which users may name without the `std::os::raw` prefix
+/// which bindgen gives them.
+const C_TYPES: &[&str] = &[
+    "c_char",
+    "c_schar",
+    "c_uchar",
+    "c_short",
+    "c_ushort",
+    "c_int",
+    "c_uint",
+    "c_long",
+    "c_ulong",
+    "c_longlong",
+    "c_ulonglong",
+    "c_float",
+    "c_double",
+    "c_void",
+];
+
+pub(crate) fn create_variadic_instantiations(
+    apis: ApiVec<PodPhase>,
+    config: &IncludeCppConfig,
+) -> ApiVec<PodPhase> {
+    apis.into_iter()
+        .flat_map(|api| -> Box<dyn Iterator<Item = Api<PodPhase>>> {
+            match &api {
+                Api::Function { name, fun, .. } if fun.variadic && fun.self_ty.is_none() => {
+                    let cpp_name = name.qualified_cpp_name();
+                    let instantiations: Vec<_> = config
+                        .variadic_instantiations(&cpp_name)
+                        .enumerate()
+                        .map(|(idx, types)| create_instantiation(name, fun, idx, types))
+                        .collect();
+                    // If there are none, we leave the original function
+                    // alone so that the function analysis can explain
+                    // why it can't be called.
+                    if instantiations.is_empty() {
+                        Box::new(std::iter::once(api))
+                    } else {
+                        Box::new(instantiations.into_iter())
+                    }
+                }
+                _ => Box::new(std::iter::once(api)),
+            }
+        })
+        .collect()
+}
+
+fn create_instantiation(
+    name: &ApiName,
+    fun: &FuncToConvert,
+    idx: usize,
+    types: &[Type],
+) -> Api<PodPhase> {
+    let ns = name.name.get_namespace();
+    let cpp_name = name.cpp_name_if_present().cloned().unwrap_or_else(|| {
+        CppOriginalName::from_final_item_of_pre_existing_qualified_name(&name.name)
+    });
+    let mut fun = fun.clone();
+    for (arg_idx, ty) in types.iter().enumerate() {
+        let arg_name = make_ident(format!("vararg{arg_idx}"));
+        let ty = to_bindgen_type(ty);
+        let arg: syn::FnArg = parse_quote! { #arg_name: #ty };
+        fun.inputs.push(FnArg::from(arg));
+    }
+    fun.variadic = false;
+    fun.synthetic_cpp = Some((
+        CppFunctionBody::FunctionCall(ns.clone(), cpp_name.to_effective_name()),
+        CppFunctionKind::Function,
+    ));
+    Api::Function {
+        name: ApiName::new_with_cpp_name(
+            ns,
+            make_ident(format!(
+                "{}_autocxx_variadic{idx}",
+                name.name.get_final_item()
+            )),
+            Some(cpp_name),
+        ),
+        fun: Box::new(fun),
+        analysis: (),
+    }
+}
+
+/// Refer to C types as bindgen would, so that the rest of our analysis
+/// recognizes them.
+pub(crate) fn to_bindgen_type(ty: &Type) -> Type {
+    match ty {
+        Type::Ptr(ptr) => {
+            let mut ptr = ptr.clone();
+            ptr.elem = Box::new(to_bindgen_type(&ptr.elem));
+            Type::Ptr(ptr)
+        }
+        Type::Path(typ) if typ.qself.is_none() && typ.path.segments.len() == 1 => {
+            let id = &typ.path.segments[0].ident;
+            if C_TYPES.contains(&id.to_string().as_str()) {
+                parse_quote! { ::std::os::raw::#id }
+            } else {
+                ty.clone()
+            }
+        }
+        _ => ty.clone(),
+    }
+}
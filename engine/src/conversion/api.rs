@@ -13,7 +13,7 @@ use syn::{
     token::{Comma, Unsafe},
 };
 
-use crate::types::{make_ident, Namespace, QualifiedName};
+use crate::types::{make_ident, CppOperator, Namespace, QualifiedName};
 use crate::{
     minisyn::{
         Attribute, FnArg, Ident, ItemConst, ItemEnum, ItemStruct, ItemType, Pat, ReturnType, Type,
@@ -54,8 +54,25 @@ pub(crate) enum TypeKind {
 /// Details about a C++ struct.
 #[derive(Debug)]
 pub(crate) struct StructDetails {
+    /// For a union, this is a struct with the same fields. The fields of
+    /// any anonymous unions or structs within it are listed as if they
+    /// were its own, as they are in C++. Any bitfields appear as the bytes
+    /// in which they're stored.
     pub(crate) item: ItemStruct,
     pub(crate) has_rvalue_reference_fields: bool,
+    pub(crate) is_union: bool,
+    pub(crate) bitfields: Vec<Bitfield>,
+}
+
+/// A bitfield within a struct. bindgen generates a getter and a setter
+/// for it, which take care of where exactly its bits are stored.
+#[derive(Debug, Clone)]
+pub(crate) struct Bitfield {
+    /// The name of the getter generated by bindgen, which is normally
+    /// the name of the field.
+    pub(crate) getter: Ident,
+    /// The type of the field, as bindgen names it.
+    pub(crate) ty: Type,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -75,6 +92,21 @@ pub(crate) enum TraitSynthesis {
     },
     AllocUninitialized(QualifiedName),
     FreeUninitialized(QualifiedName),
+    /// A C++ operator overload, which may correspond to a Rust trait
+    /// if it has the right shape.
+    Operator(CppOperator),
+    /// A call to this type's `std::hash` specialization.
+    Hash(QualifiedName),
+    /// Creation of the state needed to iterate over a type with `begin()`
+    /// and `end()` methods, which is then stepped through by `next_fn`.
+    IntoIterator {
+        container: QualifiedName,
+        state: QualifiedName,
+        next_fn: QualifiedName,
+        mutable: bool,
+    },
+    /// A step through the iteration state of the given type.
+    IteratorState(QualifiedName),
 }
 
 /// Details of a subclass constructor.
@@ -385,6 +417,21 @@ pub(crate) enum Api<T: AnalysisPhase> {
         rs_definition: Option<Box<Type>>,
         cpp_definition: String,
     },
+    /// A synthetic struct we've manufactured so that a `std::pair` or
+    /// `std::tuple` of simple types can be passed by value, and appear
+    /// in Rust as a tuple.
+    TupleStruct {
+        name: ApiName,
+        elems: Vec<crate::minisyn::Type>,
+        cpp_definition: String,
+    },
+    /// A synthetic struct we've manufactured so that a `std::variant` can
+    /// be passed by value, and which appears in Rust as an enum.
+    VariantStruct {
+        name: ApiName,
+        enum_name: Ident,
+        alternatives: Vec<VariantAlternative>,
+    },
     /// A simple note that we want to make a constructor for
     /// a `std::string` on the heap.
     StringConstructor { name: ApiName },
@@ -465,6 +512,17 @@ pub(crate) enum Api<T: AnalysisPhase> {
     },
 }
 
+/// One of the types which a `std::variant` may hold.
+#[derive(Clone, Debug)]
+pub(crate) struct VariantAlternative {
+    /// The name of the corresponding variant of the Rust enum.
+    pub(crate) name: Ident,
+    pub(crate) ty: Type,
+    /// Whether we can hold this by value in Rust. If not, the enum
+    /// holds it in a `UniquePtr`.
+    pub(crate) by_value: bool,
+}
+
 #[derive(Debug)]
 pub(crate) struct RustSubclassFnDetails {
     pub(crate) params: Punctuated<FnArg, Comma>,
@@ -491,6 +549,8 @@ impl<T: AnalysisPhase> Api<T> {
             Api::ForwardDeclaration { name, .. } => name,
             Api::OpaqueTypedef { name, .. } => name,
             Api::ConcreteType { name, .. } => name,
+            Api::TupleStruct { name, .. } => name,
+            Api::VariantStruct { name, .. } => name,
             Api::StringConstructor { name } => name,
             Api::Function { name, .. } => name,
             Api::Const { name, .. } => name,
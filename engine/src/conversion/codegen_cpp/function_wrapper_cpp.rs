@@ -6,7 +6,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use syn::{Type, TypePtr};
+use syn::{Type, TypePtr, TypeReference};
 
 use crate::conversion::{
     analysis::fun::function_wrapper::{CppConversionType, TypeConversionPolicy},
@@ -35,6 +35,7 @@ impl TypeConversionPolicy {
     ) -> Result<String, ConvertErrorFromCpp> {
         match self.cpp_conversion {
             CppConversionType::FromValueToUniquePtr => self.unique_ptr_wrapped_type(cpp_name_map),
+            CppConversionType::FromOptionalToPlacementPtr => Ok("bool".into()),
             CppConversionType::FromReferenceToPointer => {
                 let (const_string, ty) = match self.cxxbridge_type() {
                     Type::Ptr(TypePtr {
@@ -81,7 +82,9 @@ impl TypeConversionPolicy {
         // If is_return we want to avoid unnecessary std::moves because they
         // make RVO less effective
         Ok(match self.cpp_conversion {
-            CppConversionType::None | CppConversionType::FromReturnValueToPlacementPtr => {
+            CppConversionType::None
+            | CppConversionType::FromReturnValueToPlacementPtr
+            | CppConversionType::FromOptionalToPlacementPtr => {
                 Some(var_name.to_string())
             }
             CppConversionType::FromPointerToReference { .. } => Some(format!("(*{var_name})")),
@@ -104,6 +107,91 @@ impl TypeConversionPolicy {
             }
             CppConversionType::IgnoredPlacementPtrParameter => None,
             CppConversionType::FromReferenceToPointer { .. } => Some(format!("&{var_name}")),
+            CppConversionType::FromSliceToByteView => Some(format!(
+                "std::string_view(reinterpret_cast<const char*>({var_name}.data()), {var_name}.size())"
+            )),
+            // We don't know exactly what sort of view we've been given, so
+            // use a generic lambda.
+            CppConversionType::FromByteViewToSlice => Some(format!(
+                "[](auto v) {{ return rust::Slice<const uint8_t>(reinterpret_cast<const uint8_t*>(v.data()), v.size()); }}({var_name})"
+            )),
+            CppConversionType::FromSliceToSpan => {
+                Some(format!("std::span({var_name}.data(), {var_name}.size())"))
+            }
+            // std::array is an aggregate wrapping a C array, so this is
+            // just a different view of the same storage, so long as there's
+            // no padding.
+            CppConversionType::FromStdArrayToCArray => {
+                let (const_string, elem) = match self.cxxbridge_type() {
+                    Type::Reference(TypeReference {
+                        mutability: Some(_),
+                        elem,
+                        ..
+                    }) => ("", elem.as_ref()),
+                    Type::Reference(TypeReference { elem, .. }) => ("const ", elem.as_ref()),
+                    _ => panic!("Not a reference"),
+                };
+                let c_array_ref = format!(
+                    "{const_string}{}",
+                    cpp_name_map.c_array_type_to_cpp(elem, "&")?
+                );
+                Some(format!(
+                    "[&]() -> {c_array_ref} {{ static_assert(sizeof(reinterpret_cast<{c_array_ref}>({var_name})) == sizeof({var_name}), \"std::array must have the layout of a C array\"); return reinterpret_cast<{c_array_ref}>({var_name}); }}()"
+                ))
+            }
+            CppConversionType::FromPtrToOptional => Some(format!(
+                "({var_name} ? std::make_optional(*{var_name}) : std::nullopt)"
+            )),
+            CppConversionType::FromUniquePtrToOptional => Some(format!(
+                "({var_name} ? std::make_optional(std::move(*{var_name})) : std::nullopt)"
+            )),
+            CppConversionType::FromGeneratedStructToValue => Some(format!("{var_name}.to_cpp()")),
+            CppConversionType::FromValueToGeneratedStruct => Some(format!(
+                "{}::from_cpp({var_name})",
+                self.unwrapped_type_as_string(cpp_name_map)?
+            )),
+            CppConversionType::FromVoidPtrToFunctionPointer(ref fn_ty) => Some(format!(
+                "reinterpret_cast<{}>({var_name})",
+                cpp_name_map.type_to_cpp(fn_ty)?
+            )),
+            CppConversionType::FromFunctionPointerToVoidPtr => {
+                Some(format!("reinterpret_cast<void*>({var_name})"))
+            }
+            // The Rust closure is boxed up along with functions to call
+            // and drop it. Copies of the `std::function` share ownership
+            // of that box, and the last to be destroyed drops it.
+            CppConversionType::FromVoidPtrToStdFunction(ref details) => {
+                let param_types = details
+                    .param_types
+                    .iter()
+                    .map(|ty| cpp_name_map.type_to_cpp(ty))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let ret = match details.output.0 {
+                    syn::ReturnType::Default => "void".to_string(),
+                    syn::ReturnType::Type(_, ref ty) => cpp_name_map.type_to_cpp(ty)?,
+                };
+                let params = param_types
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, ty)| format!("{ty} arg{idx}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let args: String = (0..param_types.len())
+                    .map(|idx| format!(", arg{idx}"))
+                    .collect();
+                let param_types = param_types.join(", ");
+                let call_param_types = if param_types.is_empty() {
+                    "RustFunction*".to_string()
+                } else {
+                    format!("RustFunction*, {param_types}")
+                };
+                Some(format!(
+                    "[](void* f) {{ struct RustFunction {{ {ret} (*call)({call_param_types}); void (*drop)(RustFunction*); }}; std::shared_ptr<RustFunction> state(static_cast<RustFunction*>(f), [](RustFunction* s) {{ s->drop(s); }}); return autocxx_std_function<std::function<{ret}({param_types})>>{{[state]({params}) -> {ret} {{ return state->call(state.get(){args}); }}}}; }}({var_name})"
+                ))
+            }
+            CppConversionType::FromOptionalToUniquePtr => Some(format!(
+                "[](auto o) {{ return o ? std::make_unique<typename decltype(o)::value_type>(std::move(*o)) : nullptr; }}({var_name})"
+            )),
         })
     }
 }
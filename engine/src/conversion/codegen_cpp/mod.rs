@@ -8,6 +8,7 @@
 
 mod function_wrapper_cpp;
 mod new_and_delete_prelude;
+mod std_function_prelude;
 pub(crate) mod type_to_cpp;
 
 use crate::{
@@ -25,12 +26,14 @@ use type_to_cpp::CppNameMap;
 use super::{
     analysis::{
         fun::{
-            function_wrapper::{CppFunction, CppFunctionBody},
+            function_wrapper::{
+                ComparisonOperator, CppConversionType, CppFunction, CppFunctionBody,
+            },
             FnPhase, PodAndDepAnalysis,
         },
         pod::PodAnalysis,
     },
-    api::{Api, Provenance, SubclassName, TypeKind},
+    api::{Api, Provenance, SubclassName, TypeKind, VariantAlternative},
     apivec::ApiVec,
     ConvertErrorFromCpp, CppEffectiveName,
 };
@@ -44,6 +47,7 @@ enum Header {
     CxxH,
     CxxgenH,
     NewDeletePrelude,
+    StdFunctionPrelude,
 }
 
 impl Header {
@@ -67,6 +71,7 @@ impl Header {
                 format!("#include \"{prefix}{cxxgen_header_name}\"")
             }
             Header::NewDeletePrelude => new_and_delete_prelude::NEW_AND_DELETE_PRELUDE.to_string(),
+            Header::StdFunctionPrelude => std_function_prelude::STD_FUNCTION_PRELUDE.to_string(),
         }
     }
 
@@ -178,6 +183,14 @@ impl<'a> CppCodeGenerator<'a> {
 
                     self.generate_typedef(api.name(), &effective_cpp_definition)
                 }
+                Api::TupleStruct {
+                    elems,
+                    cpp_definition,
+                    ..
+                } => self.generate_tuple_struct(api.name(), elems, cpp_definition)?,
+                Api::VariantStruct { alternatives, .. } => {
+                    self.generate_variant_struct(api.name(), alternatives)?
+                }
                 Api::CType { typename, .. } => self.generate_ctype_typedef(typename),
                 Api::Subclass { .. } => deferred_apis.push(api),
                 Api::RustSubclassFn {
@@ -464,12 +477,13 @@ impl<'a> CppCodeGenerator<'a> {
         if matches!(&details.payload, CppFunctionBody::ConstructSuperclass(_)) {
             arg_list.next();
         }
+        let args: Vec<_> = arg_list.collect();
         let arg_list = if details.pass_obs_field {
             std::iter::once("*obs".to_string())
-                .chain(arg_list)
+                .chain(args.iter().cloned())
                 .join(",")
         } else {
-            arg_list.join(", ")
+            args.join(", ")
         };
         let (mut underlying_function_call, field_assignments, need_allocators) = match &details
             .payload
@@ -566,6 +580,109 @@ impl<'a> CppCodeGenerator<'a> {
                     true,
                 )
             }
+            CppFunctionBody::ThreeWayComparison(op) => {
+                let (a, b) = (&args[0], &args[1]);
+                let comparison = match op {
+                    ComparisonOperator::LessThan => {
+                        format!("({a} < {b}) ? -1 : (({b} < {a}) ? 1 : 0)")
+                    }
+                    // Anything else is unordered, e.g. a NaN in a
+                    // std::partial_ordering.
+                    ComparisonOperator::ThreeWay => format!(
+                        "[](auto r) {{ return r < 0 ? -1 : r > 0 ? 1 : r == 0 ? 0 : 2; }}({a} <=> {b})"
+                    ),
+                };
+                (comparison, "".to_string(), false)
+            }
+            CppFunctionBody::StreamToString => (
+                format!(
+                    "[&] {{ std::ostringstream autocxx_stream; autocxx_stream << {arg_list}; return autocxx_stream.str(); }}()"
+                ),
+                "".to_string(),
+                false,
+            ),
+            CppFunctionBody::IteratorBegin(state) => {
+                let (state, container) = (self.namespaced_name(state), &args[0]);
+                (
+                    format!(
+                        "std::unique_ptr<{state}>(new {state}{{{container}.begin(), {container}.end()}})"
+                    ),
+                    "".to_string(),
+                    false,
+                )
+            }
+            CppFunctionBody::IteratorNext => {
+                let state = &args[0];
+                (
+                    format!(
+                        "[&] {{ static_assert(std::is_lvalue_reference<decltype(*{state}.cur)>::value, \"iterable! types must have iterators which yield references\"); return {state}.cur == {state}.end ? nullptr : std::addressof(*{state}.cur++); }}()"
+                    ),
+                    "".to_string(),
+                    false,
+                )
+            }
+            CppFunctionBody::StdHash(ty) => {
+                // A type without a specialization gets a disabled std::hash,
+                // which can't be constructed. Explain that, rather than
+                // leaving the user to decipher template errors.
+                let ty = self.namespaced_name(ty);
+                (
+                    format!(
+                        "[&] {{ static_assert(std::is_default_constructible<std::hash<{ty}>>::value, \"{ty} is listed in hashable! but has no std::hash specialization\"); return std::hash<{ty}>{{}}({arg_list}); }}()"
+                    ),
+                    "".to_string(),
+                    false,
+                )
+            }
+            CppFunctionBody::MapLen => (format!("{}.size()", args[0]), "".to_string(), false),
+            CppFunctionBody::MapGet => {
+                let (map, key) = (&args[0], &args[1]);
+                (
+                    format!(
+                        "[&] {{ auto autocxx_it = {map}.find({key}); return autocxx_it == {map}.end() ? nullptr : std::addressof(autocxx_it->second); }}()"
+                    ),
+                    "".to_string(),
+                    false,
+                )
+            }
+            CppFunctionBody::MapContainsKey => (
+                format!("{}.count({}) != 0", args[0], args[1]),
+                "".to_string(),
+                false,
+            ),
+            CppFunctionBody::MapInsert => {
+                let (map, key, value) = (&args[0], &args[1], &args[2]);
+                (
+                    format!(
+                        "[](auto& autocxx_map, auto autocxx_key, auto autocxx_value) {{ auto autocxx_it = autocxx_map.find(autocxx_key); if (autocxx_it != autocxx_map.end()) {{ autocxx_it->second = std::move(autocxx_value); return false; }} autocxx_map.emplace(std::move(autocxx_key), std::move(autocxx_value)); return true; }}({map}, {key}, {value})"
+                    ),
+                    "".to_string(),
+                    false,
+                )
+            }
+            CppFunctionBody::MapRemove => (
+                format!("{}.erase({}) != 0", args[0], args[1]),
+                "".to_string(),
+                false,
+            ),
+            CppFunctionBody::MapEntryKey => (format!("{}.first", args[0]), "".to_string(), false),
+            CppFunctionBody::MapEntryValue => {
+                (format!("{}.second", args[0]), "".to_string(), false)
+            }
+            CppFunctionBody::FieldAccess(field) => {
+                (format!("{}.{field}", args[0]), "".to_string(), false)
+            }
+            CppFunctionBody::MethodCall(method) => (
+                format!("{}.{method}({})", args[0], args[1..].join(", ")),
+                "".to_string(),
+                false,
+            ),
+            CppFunctionBody::StaticData(var) => (var.to_cpp_name(), "".to_string(), false),
+            CppFunctionBody::StaticDataAddress(var) => (
+                format!("std::addressof({})", var.to_cpp_name()),
+                "".to_string(),
+                false,
+            ),
             CppFunctionBody::FreeUninitialized(ty) => (
                 format!("delete_appropriately<{}>(arg0);", self.namespaced_name(ty)),
                 "".to_string(),
@@ -589,6 +706,15 @@ impl<'a> CppCodeGenerator<'a> {
             );
 
             underlying_function_call = match placement_param {
+                Some(placement_param)
+                    if matches!(
+                        ret.cpp_conversion,
+                        CppConversionType::FromOptionalToPlacementPtr
+                    ) =>
+                {
+                    let tyname = self.original_name_map.type_to_cpp(ret.cxxbridge_type())?;
+                    format!("auto autocxx_optional = {call_itself}; if (autocxx_optional) {{ new({placement_param}) {tyname}(std::move(*autocxx_optional)); }} return autocxx_optional.has_value()")
+                }
                 Some(placement_param) => {
                     let tyname = self.original_name_map.type_to_cpp(ret.cxxbridge_type())?;
                     format!("new({placement_param}) {tyname}({call_itself})")
@@ -623,6 +749,67 @@ impl<'a> CppCodeGenerator<'a> {
             )
         };
         let mut headers = vec![Header::System("memory")];
+        match details.payload {
+            CppFunctionBody::StreamToString => headers.push(Header::System("sstream")),
+            CppFunctionBody::StdHash(_) => {
+                headers.push(Header::System("functional"));
+                headers.push(Header::System("type_traits"));
+            }
+            CppFunctionBody::IteratorBegin(_) => headers.push(Header::System("memory")),
+            CppFunctionBody::IteratorNext => {
+                headers.push(Header::System("memory"));
+                headers.push(Header::System("type_traits"));
+            }
+            CppFunctionBody::MapInsert => headers.push(Header::System("utility")),
+            _ => {}
+        }
+        if details
+            .argument_conversion
+            .iter()
+            .any(|conv| matches!(conv.cpp_conversion, CppConversionType::FromSliceToByteView))
+        {
+            headers.push(Header::System("string_view"));
+        }
+        if details.argument_conversion.iter().any(|conv| {
+            matches!(
+                conv.cpp_conversion,
+                CppConversionType::FromVoidPtrToStdFunction(_)
+            )
+        }) {
+            headers.push(Header::System("functional"));
+            headers.push(Header::System("utility"));
+            headers.push(Header::StdFunctionPrelude);
+        }
+        if details
+            .argument_conversion
+            .iter()
+            .any(|conv| matches!(conv.cpp_conversion, CppConversionType::FromSliceToSpan))
+        {
+            headers.push(Header::System("span"));
+        }
+        if details
+            .argument_conversion
+            .iter()
+            .any(|conv| matches!(conv.cpp_conversion, CppConversionType::FromStdArrayToCArray))
+        {
+            headers.push(Header::System("array"));
+        }
+        if details
+            .argument_conversion
+            .iter()
+            .chain(details.return_conversion.iter())
+            .any(|conv| {
+                matches!(
+                    conv.cpp_conversion,
+                    CppConversionType::FromPtrToOptional
+                        | CppConversionType::FromUniquePtrToOptional
+                        | CppConversionType::FromOptionalToUniquePtr
+                        | CppConversionType::FromOptionalToPlacementPtr
+                )
+            })
+        {
+            headers.push(Header::System("optional"));
+        }
         if need_allocators {
             headers.push(Header::System("stddef.h"));
             headers.push(Header::NewDeletePrelude);
@@ -652,6 +839,99 @@ impl<'a> CppCodeGenerator<'a> {
         })
     }
 
+    /// A struct with the same elements as some `std::pair` or `std::tuple`,
+    /// whose layout matches the struct we generate in Rust. C++ wrapper
+    /// functions convert to and from the real thing.
+    fn generate_tuple_struct(
+        &mut self,
+        tn: &QualifiedName,
+        elems: &[crate::minisyn::Type],
+        cpp_definition: &str,
+    ) -> Result<(), ConvertErrorFromCpp> {
+        let our_name = tn.get_final_item();
+        let fields = elems
+            .iter()
+            .enumerate()
+            .map(|(i, elem)| {
+                Ok(format!(
+                    "{} f{i};",
+                    self.original_name_map.type_to_cpp(elem)?
+                ))
+            })
+            .collect::<Result<Vec<_>, ConvertErrorFromCpp>>()?
+            .join(" ");
+        let field_names = (0..elems.len()).map(|i| format!("f{i}")).join(", ");
+        let gets = (0..elems.len())
+            .map(|i| format!("std::get<{i}>(t)"))
+            .join(", ");
+        self.additional_functions.push(ExtraCpp {
+            type_definition: Some(format!(
+                "struct {our_name} {{ {fields} {cpp_definition} to_cpp() const {{ return {cpp_definition}({field_names}); }} static {our_name} from_cpp(const {cpp_definition}& t) {{ return {{ {gets} }}; }} }};"
+            )),
+            headers: vec![Header::System("utility"), Header::System("tuple")],
+            ..Default::default()
+        });
+        Ok(())
+    }
+
+    /// A struct with a field for each alternative of some `std::variant`,
+    /// and the index of the one which is present, whose layout matches
+    /// the struct we generate in Rust. Alternatives which Rust can't hold
+    /// by value are owned by a pointer.
+    fn generate_variant_struct(
+        &mut self,
+        tn: &QualifiedName,
+        alternatives: &[VariantAlternative],
+    ) -> Result<(), ConvertErrorFromCpp> {
+        let our_name = tn.get_final_item();
+        let cpp_types = alternatives
+            .iter()
+            .map(|alt| self.original_name_map.type_to_cpp(&alt.ty))
+            .collect::<Result<Vec<_>, ConvertErrorFromCpp>>()?;
+        let cpp_definition = format!("std::variant<{}>", cpp_types.join(", "));
+        let mut fields = Vec::new();
+        let mut to_cpp_cases = Vec::new();
+        let mut from_cpp_cases = Vec::new();
+        for (i, (alt, cpp_type)) in alternatives.iter().zip(cpp_types.iter()).enumerate() {
+            // Anything we don't recognize as an earlier index must be the
+            // last alternative.
+            let case = if i + 1 == alternatives.len() {
+                "default".to_string()
+            } else {
+                format!("case {i}")
+            };
+            if alt.by_value {
+                fields.push(format!("{cpp_type} a{i};"));
+                to_cpp_cases.push(format!(
+                    "{case}: return {cpp_definition}(std::in_place_index<{i}>, a{i});"
+                ));
+                from_cpp_cases.push(format!("case {i}: s.a{i} = std::get<{i}>(v); break;"));
+            } else {
+                fields.push(format!("{cpp_type}* a{i};"));
+                to_cpp_cases.push(format!("{case}: return {cpp_definition}(std::in_place_index<{i}>, std::move(*std::unique_ptr<{cpp_type}>(a{i})));"));
+                from_cpp_cases.push(format!(
+                    "case {i}: s.a{i} = new {cpp_type}(std::move(std::get<{i}>(v))); break;"
+                ));
+            }
+        }
+        let fields = fields.join(" ");
+        let to_cpp_cases = to_cpp_cases.join(" ");
+        let from_cpp_cases = from_cpp_cases.join(" ");
+        self.additional_functions.push(ExtraCpp {
+            type_definition: Some(format!(
+                "struct {our_name} {{ size_t index; {fields} {cpp_definition} to_cpp() {{ switch (index) {{ {to_cpp_cases} }} }} static {our_name} from_cpp({cpp_definition} v) {{ {our_name} s{{}}; s.index = v.index(); switch (s.index) {{ {from_cpp_cases} }} return s; }} }};"
+            )),
+            headers: vec![
+                Header::System("cstddef"),
+                Header::System("memory"),
+                Header::System("utility"),
+                Header::System("variant"),
+            ],
+            ..Default::default()
+        });
+        Ok(())
+    }
+
     fn generate_subclass(
         &mut self,
         superclass: &QualifiedName,
@@ -0,0 +1,29 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use indoc::indoc;
+
+/// A `std::function` made from a Rust closure, which can only become
+/// a `std::function` of exactly the signature given in `std_function!`.
+/// One of a different signature might otherwise be made from it, silently
+/// converting the parameters and return value. A conversion function
+/// which isn't a template is preferred where both would do, so the
+/// template is only used, and fails, if the signatures differ.
+pub(super) static STD_FUNCTION_PRELUDE: &str = indoc! {"
+    #ifndef AUTOCXX_STD_FUNCTION_PRELUDE
+    #define AUTOCXX_STD_FUNCTION_PRELUDE
+    template <typename F> struct autocxx_std_function {
+      F f;
+      operator F() && { return std::move(f); }
+      template <typename T> operator T() && {
+        static_assert(sizeof(T) == 0, \"the signature given in std_function! doesn't match the C++ parameter\");
+        return T();
+      }
+    };
+    #endif // AUTOCXX_STD_FUNCTION_PRELUDE
+"};
@@ -15,7 +15,7 @@ use indexmap::map::IndexMap as HashMap;
 use itertools::Itertools;
 use quote::ToTokens;
 use std::iter::once;
-use syn::{Token, Type};
+use syn::{Expr, ExprLit, Lit, Token, Type, TypeArray};
 
 /// Map from QualifiedName to original C++ name. Original C++ name does not
 /// include the namespace; this can be assumed to be the same as the namespace
@@ -109,6 +109,11 @@ impl CppNameMap {
             }
             Type::Reference(typr) => match &*typr.elem {
                 Type::Path(typ) if typ.path.is_ident("str") => Ok("rust::Str".into()),
+                Type::Slice(typs) => Ok(format!(
+                    "rust::Slice<{}{}>",
+                    get_mut_string(&typr.mutability),
+                    self.type_to_cpp(typs.elem.as_ref())?
+                )),
                 _ => Ok(format!(
                     "{}{}&",
                     get_mut_string(&typr.mutability),
@@ -120,9 +125,26 @@ impl CppNameMap {
                 get_mut_string(&typp.mutability),
                 self.type_to_cpp(typp.elem.as_ref())?
             )),
-            Type::Array(_)
-            | Type::BareFn(_)
-            | Type::Group(_)
+            // This is how cxx represents Rust arrays.
+            Type::Array(typa) => Ok(format!(
+                "std::array<{}, {}>",
+                self.type_to_cpp(typa.elem.as_ref())?,
+                Self::array_len_to_cpp(typa)?
+            )),
+            // A C function pointer.
+            Type::BareFn(typf) => {
+                let params: Result<Vec<_>, _> = typf
+                    .inputs
+                    .iter()
+                    .map(|input| self.type_to_cpp(&input.ty))
+                    .collect();
+                let ret = match &typf.output {
+                    syn::ReturnType::Default => "void".to_string(),
+                    syn::ReturnType::Type(_, ty) => self.type_to_cpp(ty)?,
+                };
+                Ok(format!("{ret}(*)({})", params?.join(", ")))
+            }
+            Type::Group(_)
             | Type::ImplTrait(_)
             | Type::Infer(_)
             | Type::Macro(_)
@@ -140,6 +162,33 @@ impl CppNameMap {
         }
     }
 
+    /// Spell out a declarator for a C array, such as `float(&)[4][4]`
+    /// for the `&` declarator and a `[[f32; 4]; 4]`.
+    pub(crate) fn c_array_type_to_cpp(
+        &self,
+        ty: &Type,
+        declarator: &str,
+    ) -> Result<String, ConvertErrorFromCpp> {
+        match ty {
+            Type::Array(typa) => self.c_array_type_to_cpp(
+                typa.elem.as_ref(),
+                &format!("{declarator}[{}]", Self::array_len_to_cpp(typa)?),
+            ),
+            _ => Ok(format!("{}({declarator})", self.type_to_cpp(ty)?)),
+        }
+    }
+
+    fn array_len_to_cpp(typa: &TypeArray) -> Result<String, ConvertErrorFromCpp> {
+        match &typa.len {
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(len), ..
+            }) => Ok(len.base10_digits().to_string()),
+            _ => Err(ConvertErrorFromCpp::UnsupportedType(
+                typa.to_token_stream().to_string(),
+            )),
+        }
+    }
+
     /// Check an individual item in the name map. Returns a thing if
     /// it's an inner type, otherwise returns none.
     pub(crate) fn get(&self, name: &QualifiedName) -> Option<&CppOriginalName> {
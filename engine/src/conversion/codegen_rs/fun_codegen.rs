@@ -16,7 +16,8 @@ use syn::{
     parse_quote,
     punctuated::Punctuated,
     token::{Comma, Unsafe},
-    Attribute, ForeignItem, Ident, ImplItem, Item, ReturnType,
+    Attribute, ForeignItem, Ident, ImplItem, Item, ReturnType, TraitItem, Type, TypePtr,
+    TypeReference,
 };
 
 use super::{
@@ -29,10 +30,11 @@ use super::{
 use crate::{
     conversion::{
         analysis::fun::{
-            function_wrapper::TypeConversionPolicy, ArgumentAnalysis, FnAnalysis, FnKind,
-            MethodKind, RustRenameStrategy, TraitMethodDetails,
+            function_wrapper::{RustConversionType, TypeConversionPolicy},
+            ArgumentAnalysis, FnAnalysis, FnKind, MethodKind, RustRenameStrategy,
+            TraitMethodDetails, TraitMethodOutput, TraitReceiver,
         },
-        api::UnsafetyNeeded,
+        api::{TraitImplSignature, UnsafetyNeeded},
     },
     minisyn::{minisynize_vec, FnArg},
     types::QualifiedName,
@@ -108,7 +110,7 @@ pub(super) fn gen_function(
 
     let mut cpp_name_attr = Vec::new();
     let mut impl_entry = None;
-    let mut trait_impl_entry = None;
+    let mut trait_impl_entries = Vec::new();
     let always_unsafe_due_to_trait_definition = match kind {
         FnKind::TraitMethod { ref details, .. } => details.trait_call_is_unsafe,
         _ => false,
@@ -123,6 +125,7 @@ pub(super) fn gen_function(
         non_pod_types,
         ret_type: &ret_type,
         ret_conversion: &ret_conversion,
+        throws: analysis.throws,
     };
     // In rare occasions, we might need to give an explicit lifetime.
     let (lifetime_tokens, params, ret_type) = add_explicit_lifetime_if_necessary(
@@ -157,7 +160,8 @@ pub(super) fn gen_function(
                 ));
             }
             FnKind::TraitMethod { ref details, .. } => {
-                trait_impl_entry = Some(fn_generator.generate_trait_impl(details));
+                trait_impl_entries.push(fn_generator.generate_trait_impl(details));
+                trait_impl_entries.extend(fn_generator.generate_implied_trait_impls(details));
             }
             _ => {
                 // Generate plain old function
@@ -191,6 +195,12 @@ pub(super) fn gen_function(
     // which the user has declared.
     let params = unqualify_params_minisyn(params);
     let ret_type = unqualify_ret_type(ret_type.into_owned());
+    // cxx will catch any C++ exception and report it as a Result.
+    let ret_type = if analysis.throws {
+        wrap_ret_type_in_result(&ret_type, None)
+    } else {
+        ret_type
+    };
     // And we need to make an attribute for the namespace that the function
     // itself is in.
     let namespace_attr = if name.get_namespace().is_empty() || wrapper_function_needed {
@@ -214,7 +224,7 @@ pub(super) fn gen_function(
     RsCodegenResult {
         extern_c_mod_items: vec![extern_c_mod_item],
         impl_entry,
-        trait_impl_entry,
+        trait_impl_entries,
         output_mod_items,
         ..Default::default()
     }
@@ -232,6 +242,7 @@ struct FnGenerator<'a> {
     always_unsafe_due_to_trait_definition: bool,
     doc_attrs: &'a Vec<Attribute>,
     non_pod_types: &'a HashSet<QualifiedName>,
+    throws: bool,
 }
 
 impl<'a> FnGenerator<'a> {
@@ -240,6 +251,7 @@ impl<'a> FnGenerator<'a> {
         avoid_self: bool,
         parameter_reordering: &Option<Vec<usize>>,
         ret_type: Option<ReturnType>,
+        receiver: Option<&TraitReceiver>,
     ) -> (
         Option<TokenStream>,
         Punctuated<FnArg, Comma>,
@@ -255,15 +267,23 @@ impl<'a> FnGenerator<'a> {
             .unwrap_or_else(|| Cow::Borrowed(self.ret_type));
         let mut any_conversion_requires_unsafe = false;
         let mut variable_counter = 0usize;
-        for pd in self.param_details {
-            let wrapper_arg_name: syn::Pat = if pd.self_type.is_some() && !avoid_self {
-                parse_quote!(self)
-            } else {
-                pd.name.clone().into()
+        for (param_idx, pd) in self.param_details.iter().enumerate() {
+            // Some traits dictate how they take `self`, irrespective of
+            // whether the C++ is a method or a free function.
+            let receiver = receiver.filter(|_| param_idx == 0);
+            let wrapper_arg_name: syn::Pat =
+                if receiver.is_some() || (pd.self_type.is_some() && !avoid_self) {
+                    parse_quote!(self)
+                } else {
+                    pd.name.clone().into()
+                };
+            let arg_expr = match receiver {
+                Some(TraitReceiver::PinnedMutableReference) => parse_quote! { self.as_mut() },
+                _ => parse_quote! { #wrapper_arg_name },
             };
             let rust_for_param = pd
                 .conversion
-                .rust_conversion(parse_quote! { #wrapper_arg_name }, &mut variable_counter);
+                .rust_conversion(arg_expr, &mut variable_counter);
             match rust_for_param {
                 RustParamConversion::Param {
                     ty,
@@ -275,6 +295,12 @@ impl<'a> FnGenerator<'a> {
                     local_variables.append(&mut these_local_variables);
                     if pd.is_placement_return_destination {
                         ptr_arg_name = Some(conversion);
+                    } else if let Some(receiver) = receiver {
+                        wrapper_params.push(match receiver {
+                            TraitReceiver::Value => parse_quote!(self),
+                            TraitReceiver::Reference => parse_quote!(&self),
+                            TraitReceiver::PinnedMutableReference => parse_quote!(&mut self),
+                        });
                     } else {
                         let param_mutability = pd.conversion.rust_conversion.requires_mutability();
                         wrapper_params.push(parse_quote!(
@@ -284,6 +310,13 @@ impl<'a> FnGenerator<'a> {
                     any_conversion_requires_unsafe =
                         conversion_requires_unsafe || any_conversion_requires_unsafe;
                 }
+                RustParamConversion::OutParam {
+                    conversion,
+                    local_variables: mut these_local_variables,
+                } => {
+                    arg_list.push(conversion);
+                    local_variables.append(&mut these_local_variables);
+                }
                 RustParamConversion::ReturnValue { ty } => {
                     ptr_arg_name = Some(pd.name.to_token_stream());
                     ret_type = Cow::Owned(parse_quote! {
@@ -296,13 +329,19 @@ impl<'a> FnGenerator<'a> {
         if let Some(parameter_reordering) = &parameter_reordering {
             wrapper_params = Self::reorder_parameters(wrapper_params, parameter_reordering);
         }
-        let (lifetime_tokens, wrapper_params, ret_type) = add_explicit_lifetime_if_necessary(
-            self.param_details,
-            wrapper_params,
-            ret_type,
-            self.non_pod_types,
-            self.ret_conversion,
-        );
+        // Where the trait determines how we take `self`, it also determines
+        // the lifetimes, and elision gives exactly what it wants.
+        let (lifetime_tokens, wrapper_params, ret_type) = if receiver.is_some() {
+            (None, wrapper_params, ret_type)
+        } else {
+            add_explicit_lifetime_if_necessary(
+                self.param_details,
+                wrapper_params,
+                ret_type,
+                self.non_pod_types,
+                self.ret_conversion,
+            )
+        };
 
         let cxxbridge_name = self.cxxbridge_name;
         let call_body = MaybeUnsafeStmt::maybe_unsafe(
@@ -327,8 +366,14 @@ impl<'a> FnGenerator<'a> {
                 //   do_return_conversion(temp_ret_val)
                 // by returning a vector of MaybeUnsafes within call_body.
                 let expr = maybe_unsafes_to_tokens(vec![call_body], context_is_unsafe);
-                let conv =
-                    ret_conversion.rust_conversion(parse_quote! { #expr }, &mut variable_counter);
+                // If the function throws, cxx gives us a Result, and we need to
+                // convert whatever's inside it.
+                let conversion_input = if self.throws {
+                    parse_quote! { autocxx_ret }
+                } else {
+                    parse_quote! { #expr }
+                };
+                let conv = ret_conversion.rust_conversion(conversion_input, &mut variable_counter);
                 let (conversion, requires_unsafe, ty) = match conv {
                     RustParamConversion::Param {
                         local_variables, ..
@@ -343,6 +388,13 @@ impl<'a> FnGenerator<'a> {
                         "Unexpected - return type is supposed to be converted to a return type"
                     ),
                 };
+                let conversion = if self.throws {
+                    quote! {
+                        (#expr).map(|autocxx_ret| #conversion)
+                    }
+                } else {
+                    conversion
+                };
                 (
                     if requires_unsafe {
                         MaybeUnsafeStmt::NeedsUnsafe(conversion)
@@ -354,6 +406,7 @@ impl<'a> FnGenerator<'a> {
             }
             _ => (call_body, ret_type),
         };
+        let (call_body, ret_type) = self.return_out_params(call_body, ret_type, context_is_unsafe);
 
         let call_stmts = if let Some(ptr_arg_name) = ptr_arg_name {
             let mut closure_stmts = local_variables;
@@ -374,6 +427,14 @@ impl<'a> FnGenerator<'a> {
             call_stmts
         };
         let call_body = maybe_unsafes_to_tokens(call_stmts, context_is_unsafe);
+        let ret_type = if self.throws {
+            Cow::Owned(wrap_ret_type_in_result(
+                &ret_type,
+                Some(quote! { cxx::Exception }),
+            ))
+        } else {
+            ret_type
+        };
         (lifetime_tokens, wrapper_params, ret_type, call_body)
     }
 
@@ -384,7 +445,7 @@ impl<'a> FnGenerator<'a> {
         impl_block_type_name: &QualifiedName,
     ) -> Box<ImplBlockDetails> {
         let (lifetime_tokens, wrapper_params, ret_type, call_body) =
-            self.common_parts(avoid_self, &None, None);
+            self.common_parts(avoid_self, &None, None, None);
         let rust_name = make_ident(self.rust_name);
         let unsafety = self.unsafety.wrapper_token();
         let doc_attrs = self.doc_attrs;
@@ -401,20 +462,156 @@ impl<'a> FnGenerator<'a> {
     }
 
     /// Generate an 'impl Trait for Type { methods-go-here }' in its entrety.
-    fn generate_trait_impl(&self, details: &TraitMethodDetails) -> Box<TraitImplBlockDetails> {
-        let (lifetime_tokens, wrapper_params, ret_type, call_body) =
-            self.common_parts(details.avoid_self, &details.parameter_reordering, None);
+    fn generate_trait_impl(&self, details: &TraitMethodDetails) -> TraitImplBlockDetails {
+        let ret_type = match details.output {
+            TraitMethodOutput::Ordering { .. } => {
+                Some(parse_quote! { -> Option<::core::cmp::Ordering> })
+            }
+            TraitMethodOutput::Formatted => Some(parse_quote! { -> ::core::fmt::Result }),
+            TraitMethodOutput::Hashed => Some(ReturnType::Default),
+            TraitMethodOutput::IntoIterator { .. } => Some(parse_quote! { -> Self::IntoIter }),
+            _ => None,
+        };
+        let (lifetime_tokens, mut wrapper_params, ret_type, call_body) = self.common_parts(
+            details.avoid_self,
+            &details.parameter_reordering,
+            ret_type,
+            details.receiver.as_ref(),
+        );
+        let call_body = match details.output {
+            TraitMethodOutput::Ordering { .. } => quote! {
+                let autocxx_ordering = { #call_body };
+                match autocxx_ordering {
+                    -1 => Some(::core::cmp::Ordering::Less),
+                    0 => Some(::core::cmp::Ordering::Equal),
+                    1 => Some(::core::cmp::Ordering::Greater),
+                    _ => None,
+                }
+            },
+            TraitMethodOutput::Formatted => {
+                wrapper_params.push(parse_quote! { f: &mut ::core::fmt::Formatter<'_> });
+                quote! {
+                    let autocxx_string = { #call_body };
+                    f.write_str(&autocxx_string.to_string_lossy())
+                }
+            }
+            TraitMethodOutput::Hashed => {
+                wrapper_params.push(parse_quote! { state: &mut H });
+                quote! {
+                    state.write_usize({ #call_body })
+                }
+            }
+            TraitMethodOutput::IntoIterator { mutable: false, .. } => quote! {
+                autocxx::CppIterator::new({ #call_body })
+            },
+            TraitMethodOutput::IntoIterator { mutable: true, .. } => quote! {
+                autocxx::CppIteratorMut::new({ #call_body })
+            },
+            _ => call_body,
+        };
+        let generics = match details.output {
+            TraitMethodOutput::Hashed => Some(quote! { <H: ::core::hash::Hasher> }),
+            _ => lifetime_tokens,
+        };
         let doc_attrs = self.doc_attrs;
         let unsafety = self.unsafety.wrapper_token();
         let key = details.trt.clone();
         let method_name = &details.method_name;
-        let item = parse_quote! {
+        let mut items = Vec::new();
+        match &details.output {
+            TraitMethodOutput::AssociatedType => {
+                let output_type = match ret_type.as_ref() {
+                    ReturnType::Type(_, ty) => match ty.as_ref() {
+                        Type::Reference(TypeReference { elem, .. }) => elem.as_ref().clone(),
+                        ty => ty.clone(),
+                    },
+                    ReturnType::Default => parse_quote! { () },
+                };
+                items.push(parse_quote! {
+                    type Output = #output_type;
+                });
+            }
+            TraitMethodOutput::IteratorItem => {
+                if let ReturnType::Type(_, ty) = ret_type.as_ref() {
+                    if let Type::Ptr(TypePtr { elem, .. }) = ty.as_ref() {
+                        items.push(parse_quote! {
+                            type Item = #elem;
+                        });
+                    }
+                }
+            }
+            TraitMethodOutput::IntoIterator { state, mutable } => {
+                let state = state.to_type_path();
+                let state_item = quote! { <#state as autocxx::CppIteratorState>::Item };
+                let (item, iter): (Type, Type) = if *mutable {
+                    (
+                        parse_quote! { ::core::pin::Pin<&'a mut #state_item> },
+                        parse_quote! { autocxx::CppIteratorMut<'a, #state> },
+                    )
+                } else {
+                    (
+                        parse_quote! { &'a #state_item },
+                        parse_quote! { autocxx::CppIterator<'a, #state> },
+                    )
+                };
+                items.push(parse_quote! {
+                    type Item = #item;
+                });
+                items.push(parse_quote! {
+                    type IntoIter = #iter;
+                });
+            }
+            _ => {}
+        }
+        items.push(parse_quote! {
             #(#doc_attrs)*
-            #unsafety fn #method_name #lifetime_tokens ( #wrapper_params ) #ret_type {
+            #unsafety fn #method_name #generics ( #wrapper_params ) #ret_type {
                 #call_body
             }
+        });
+        TraitImplBlockDetails { items, key }
+    }
+
+    /// Some traits imply others which we can implement in terms of them,
+    /// e.g. `Ord` from a total `PartialOrd`. Generate those to go alongside
+    /// the trait from [`Self::generate_trait_impl`].
+    fn generate_implied_trait_impls(
+        &self,
+        details: &TraitMethodDetails,
+    ) -> Vec<TraitImplBlockDetails> {
+        let ty = &details.trt.ty;
+        let implied_trait = |trait_signature: Type, items: Vec<TraitItem>| TraitImplBlockDetails {
+            items,
+            key: TraitImplSignature {
+                ty: ty.clone(),
+                trait_signature: trait_signature.into(),
+                unsafety: None,
+            },
         };
-        Box::new(TraitImplBlockDetails { item, key })
+        match details.output {
+            // `Eq` requires `PartialEq`, which we derive from the ordering
+            // if C++ doesn't provide it.
+            TraitMethodOutput::Ordering { total: true, .. } => vec![
+                implied_trait(parse_quote! { ::core::cmp::Eq }, Vec::new()),
+                implied_trait(
+                    parse_quote! { ::core::cmp::Ord },
+                    vec![parse_quote! {
+                        fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                            self.partial_cmp(other).unwrap()
+                        }
+                    }],
+                ),
+            ],
+            TraitMethodOutput::Formatted => vec![implied_trait(
+                parse_quote! { ::core::fmt::Debug },
+                vec![parse_quote! {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        ::core::fmt::Display::fmt(self, f)
+                    }
+                }],
+            )],
+            _ => Vec::new(),
+        }
     }
 
     /// Generate a 'impl Type { methods-go-here }' item which is a constructor
@@ -425,7 +622,7 @@ impl<'a> FnGenerator<'a> {
     ) -> Box<ImplBlockDetails> {
         let ret_type: ReturnType = parse_quote! { -> impl autocxx::moveit::new::New<Output=Self> };
         let (lifetime_tokens, wrapper_params, ret_type, call_body) =
-            self.common_parts(true, &None, Some(ret_type));
+            self.common_parts(true, &None, Some(ret_type), None);
         let rust_name = make_ident(self.rust_name);
         let doc_attrs = self.doc_attrs;
         let unsafety = self.unsafety.wrapper_token();
@@ -446,7 +643,7 @@ impl<'a> FnGenerator<'a> {
     /// Generate a function call wrapper
     fn generate_function_impl(&self) -> Item {
         let (lifetime_tokens, wrapper_params, ret_type, call_body) =
-            self.common_parts(false, &None, None);
+            self.common_parts(false, &None, None, None);
         let rust_name = make_ident(self.rust_name);
         let doc_attrs = self.doc_attrs;
         let unsafety = self.unsafety.wrapper_token();
@@ -458,6 +655,68 @@ impl<'a> FnGenerator<'a> {
         })
     }
 
+    /// Any out-params are returned as `UniquePtr`s after the C++ return
+    /// value, if there is one.
+    fn return_out_params<'b>(
+        &self,
+        call_body: MaybeUnsafeStmt,
+        ret_type: Cow<'b, ReturnType>,
+        context_is_unsafe: bool,
+    ) -> (MaybeUnsafeStmt, Cow<'b, ReturnType>) {
+        let out_params: Vec<_> = self
+            .param_details
+            .iter()
+            .filter(|pd| {
+                matches!(
+                    pd.conversion.rust_conversion,
+                    RustConversionType::FromUniquePtrOutParamToPtr
+                )
+            })
+            .collect();
+        if out_params.is_empty() {
+            return (call_body, ret_type);
+        }
+        let ret_val = make_ident("autocxx_ret");
+        let (mut values, mut tys): (Vec<TokenStream>, Vec<Type>) = match ret_type.as_ref() {
+            ReturnType::Type(_, ty) => (vec![quote! { #ret_val }], vec![ty.as_ref().clone()]),
+            ReturnType::Default => (Vec::new(), Vec::new()),
+        };
+        let has_ret_val = !values.is_empty();
+        for pd in out_params {
+            let ty = match pd.conversion.cxxbridge_type() {
+                Type::Ptr(TypePtr { elem, .. }) => match elem.as_ref() {
+                    Type::Ptr(TypePtr { elem, .. }) => elem,
+                    _ => panic!("Not a ptr to a ptr"),
+                },
+                _ => panic!("Not a ptr"),
+            };
+            let name = &pd.name;
+            values.push(maybe_unsafes_to_tokens(
+                vec![MaybeUnsafeStmt::needs_unsafe(
+                    quote! { cxx::UniquePtr::from_raw(#name) },
+                )],
+                context_is_unsafe,
+            ));
+            tys.push(parse_quote! { cxx::UniquePtr<#ty> });
+        }
+        let (value, ty) = if values.len() == 1 {
+            (values.remove(0), tys.remove(0))
+        } else {
+            (quote! { ( #(#values),* ) }, parse_quote! { ( #(#tys),* ) })
+        };
+        let expr = maybe_unsafes_to_tokens(vec![call_body], context_is_unsafe);
+        let body = match (self.throws, has_ret_val) {
+            (true, true) => quote! { (#expr).map(|#ret_val| #value) },
+            (true, false) => quote! { (#expr).map(|_| #value) },
+            (false, true) => quote! { { let #ret_val = #expr; #value } },
+            (false, false) => quote! { { #expr; #value } },
+        };
+        (
+            MaybeUnsafeStmt::new(body),
+            Cow::Owned(parse_quote! { -> #ty }),
+        )
+    }
+
     fn reorder_parameters(
         params: Punctuated<FnArg, Comma>,
         parameter_ordering: &[usize],
@@ -469,3 +728,17 @@ impl<'a> FnGenerator<'a> {
             .collect()
     }
 }
+
+/// Wraps a return type in a `Result`, such that C++ exceptions can be
+/// reported to Rust. Within the `cxx::bridge` mod cxx expects a plain
+/// `Result<T>`, whereas elsewhere we need to spell out `cxx::Exception`.
+fn wrap_ret_type_in_result(ret_type: &ReturnType, error_type: Option<TokenStream>) -> ReturnType {
+    let ty = match ret_type {
+        ReturnType::Default => quote! { () },
+        ReturnType::Type(_, ty) => ty.to_token_stream(),
+    };
+    match error_type {
+        Some(error_type) => parse_quote! { -> ::core::result::Result<#ty, #error_type> },
+        None => parse_quote! { -> Result<#ty> },
+    }
+}
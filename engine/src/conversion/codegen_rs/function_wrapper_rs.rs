@@ -29,8 +29,18 @@ pub(super) enum RustParamConversion {
     ReturnValue {
         ty: Type,
     },
+    /// A parameter which doesn't appear in the wrapper function because
+    /// the C++ uses it to give us part of the return value.
+    OutParam {
+        local_variables: Vec<MaybeUnsafeStmt>,
+        conversion: TokenStream,
+    },
 }
 
+/// The name of the variable into which a C++ function puts the value of
+/// a `std::optional`, if there is one.
+const OPTIONAL_OUT_VAR: &str = "optional_out";
+
 impl TypeConversionPolicy {
     pub(super) fn rust_conversion(&self, var: Expr, counter: &mut usize) -> RustParamConversion {
         match self.rust_conversion {
@@ -216,6 +226,242 @@ impl TypeConversionPolicy {
                     conversion_requires_unsafe: false,
                 }
             }
+            RustConversionType::FromAsRefBytes => RustParamConversion::Param {
+                ty: parse_quote! { &(impl AsRef<[u8]> + ?Sized) },
+                local_variables: Vec::new(),
+                conversion: quote! { #var .as_ref() },
+                conversion_requires_unsafe: false,
+            },
+            RustConversionType::FromOptionToPtr => {
+                let ty = match self.cxxbridge_type() {
+                    Type::Ptr(TypePtr { elem, .. }) => elem,
+                    _ => panic!("Not a ptr"),
+                };
+                RustParamConversion::Param {
+                    ty: parse_quote! { Option<#ty> },
+                    local_variables: Vec::new(),
+                    conversion: quote! {
+                        #var .as_ref().map_or(::core::ptr::null(), |v| v as *const _)
+                    },
+                    conversion_requires_unsafe: false,
+                }
+            }
+            RustConversionType::FromOptionToUniquePtr => {
+                let ty = self.cxxbridge_type();
+                RustParamConversion::Param {
+                    ty: parse_quote! { Option<#ty> },
+                    local_variables: Vec::new(),
+                    conversion: quote! { #var .unwrap_or_else(cxx::UniquePtr::null) },
+                    conversion_requires_unsafe: false,
+                }
+            }
+            RustConversionType::FromUniquePtrToOption => {
+                let ty = self.cxxbridge_type();
+                RustParamConversion::Param {
+                    ty: parse_quote! { Option<#ty> },
+                    local_variables: Vec::new(),
+                    conversion: quote! { Some(#var).filter(|v| !v.is_null()) },
+                    conversion_requires_unsafe: false,
+                }
+            }
+            RustConversionType::FromBoolToOption => {
+                let ty = self.cxxbridge_type();
+                let out_var = make_ident(OPTIONAL_OUT_VAR);
+                RustParamConversion::Param {
+                    ty: parse_quote! { Option<#ty> },
+                    local_variables: Vec::new(),
+                    conversion: quote! {
+                        if #var {
+                            Some(unsafe { #out_var.assume_init() })
+                        } else {
+                            None
+                        }
+                    },
+                    conversion_requires_unsafe: false,
+                }
+            }
+            RustConversionType::FromOptionalOutParamToPtr => {
+                let ty = match self.cxxbridge_type() {
+                    Type::Ptr(TypePtr { elem, .. }) => elem,
+                    _ => panic!("Not a ptr"),
+                };
+                let out_var = make_ident(OPTIONAL_OUT_VAR);
+                RustParamConversion::OutParam {
+                    local_variables: vec![MaybeUnsafeStmt::new(quote! {
+                        let mut #out_var = ::core::mem::MaybeUninit::< #ty >::uninit();
+                    })],
+                    conversion: quote! { #out_var.as_mut_ptr() },
+                }
+            }
+            RustConversionType::FromUniquePtrOutParamToPtr => {
+                let ty = match self.cxxbridge_type() {
+                    Type::Ptr(TypePtr { elem, .. }) => elem,
+                    _ => panic!("Not a ptr"),
+                };
+                RustParamConversion::OutParam {
+                    local_variables: vec![MaybeUnsafeStmt::new(quote! {
+                        let mut #var: #ty = ::core::ptr::null_mut();
+                    })],
+                    conversion: quote! { &mut #var },
+                }
+            }
+            RustConversionType::FromPtrToOptionalRef => {
+                let ty = match self.cxxbridge_type() {
+                    Type::Ptr(TypePtr { elem, .. }) => elem,
+                    _ => panic!("Not a ptr"),
+                };
+                RustParamConversion::Param {
+                    ty: parse_quote! { Option<&#ty> },
+                    local_variables: Vec::new(),
+                    conversion: quote! { #var .as_ref() },
+                    conversion_requires_unsafe: true,
+                }
+            }
+            RustConversionType::FromClosureToStdFunction(ref details) => {
+                let param_types = &details.param_types;
+                let output = &details.output;
+                let closure_ty = quote! { dyn FnMut(#(#param_types),*) #output + Send };
+                let arg_names: Vec<_> = (0..param_types.len())
+                    .map(|idx| make_ident(format!("arg{idx}")))
+                    .collect();
+                let var_name = quote! { #var }.to_string();
+                let state = make_ident(format!("{var_name}_RustFunction"));
+                let call = make_ident(format!("{var_name}_call"));
+                let drop = make_ident(format!("{var_name}_drop"));
+                // The C++ `std::function` owns this box, and calls the drop
+                // function when the last copy of it is destroyed. It may
+                // be copied and called from any thread, hence the mutex.
+                // This layout must match that which the C++ expects.
+                RustParamConversion::Param {
+                    ty: parse_quote! { impl FnMut(#(#param_types),*) #output + Send + 'static },
+                    local_variables: vec![
+                        MaybeUnsafeStmt::new(quote! {
+                            #[allow(non_camel_case_types)]
+                            #[repr(C)]
+                            struct #state {
+                                call: unsafe extern "C" fn(*mut #state, #(#param_types),*) #output,
+                                drop: unsafe extern "C" fn(*mut #state),
+                                closure: ::std::sync::Mutex<Box<#closure_ty>>,
+                            }
+                        }),
+                        MaybeUnsafeStmt::new(quote! {
+                            unsafe extern "C" fn #call(state: *mut #state, #(#arg_names: #param_types),*) #output {
+                                let state = unsafe { &*state };
+                                let mut closure = state.closure.lock().unwrap_or_else(|e| e.into_inner());
+                                closure(#(#arg_names),*)
+                            }
+                        }),
+                        MaybeUnsafeStmt::new(quote! {
+                            unsafe extern "C" fn #drop(state: *mut #state) {
+                                ::core::mem::drop(unsafe { Box::from_raw(state) });
+                            }
+                        }),
+                    ],
+                    conversion: quote! {
+                        Box::into_raw(Box::new(#state {
+                            call: #call,
+                            drop: #drop,
+                            closure: ::std::sync::Mutex::new(Box::new(#var)),
+                        })).cast()
+                    },
+                    conversion_requires_unsafe: false,
+                }
+            }
+            RustConversionType::FromPtrToStaticRef => {
+                let (is_mut, ty) = match self.cxxbridge_type() {
+                    Type::Ptr(TypePtr {
+                        mutability, elem, ..
+                    }) => (mutability.is_some(), elem),
+                    _ => panic!("Not a ptr"),
+                };
+                let (ty, conversion) = if is_mut {
+                    (
+                        parse_quote! { ::core::pin::Pin<&'static mut #ty> },
+                        quote! { ::core::pin::Pin::new_unchecked(&mut * #var) },
+                    )
+                } else {
+                    (parse_quote! { &'static #ty }, quote! { &* #var })
+                };
+                RustParamConversion::Param {
+                    ty,
+                    local_variables: Vec::new(),
+                    conversion,
+                    conversion_requires_unsafe: true,
+                }
+            }
+            RustConversionType::FromFunctionPointerToVoidPtr(ref ty) => {
+                RustParamConversion::Param {
+                    ty: ty.clone().into(),
+                    local_variables: Vec::new(),
+                    conversion: quote! {
+                        #var .map_or(::core::ptr::null_mut(), |f| f as *const () as *mut _)
+                    },
+                    conversion_requires_unsafe: false,
+                }
+            }
+            // Both are pointer-sized, and a null pointer is `None`.
+            RustConversionType::FromVoidPtrToFunctionPointer(ref ty) => {
+                RustParamConversion::Param {
+                    ty: ty.clone().into(),
+                    local_variables: Vec::new(),
+                    conversion: quote! {
+                        ::core::mem::transmute::<_, #ty>(#var)
+                    },
+                    conversion_requires_unsafe: true,
+                }
+            }
+            RustConversionType::FromClosureToTrampoline(ref details) => {
+                let param_types = &details.param_types;
+                let closure_param_types: Vec<_> = param_types
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, _)| *idx != details.user_data_idx)
+                    .map(|(_, ty)| ty)
+                    .collect();
+                let output = &details.output;
+                let closure_ty = quote! { dyn FnMut(#(#closure_param_types),*) #output };
+                let arg_names: Vec<_> = (0..param_types.len())
+                    .map(|idx| make_ident(format!("arg{idx}")))
+                    .collect();
+                let user_data = &arg_names[details.user_data_idx];
+                let call_args = arg_names.iter().filter(|arg_name| *arg_name != user_data);
+                let trampoline = make_ident(format!("{}_trampoline", quote! { #var }));
+                // We box the closure so that the trampoline needn't know its
+                // type. The box stays on our stack until the C++ function
+                // returns, and the user data is a pointer to it, which is
+                // why the function is `unsafe`: the C++ mustn't keep it.
+                RustParamConversion::Param {
+                    ty: parse_quote! { impl FnMut(#(#closure_param_types),*) #output },
+                    local_variables: vec![
+                        MaybeUnsafeStmt::new(quote! {
+                            let mut #var: Box<#closure_ty + '_> = Box::new(#var);
+                        }),
+                        MaybeUnsafeStmt::new(quote! {
+                            unsafe extern "C" fn #trampoline(#(#arg_names: #param_types),*) #output {
+                                let callback = unsafe { &mut *(#user_data as *mut Box<#closure_ty>) };
+                                callback(#(#call_args),*)
+                            }
+                        }),
+                    ],
+                    conversion: quote! { #trampoline as *const () as *mut _ },
+                    conversion_requires_unsafe: false,
+                }
+            }
+            RustConversionType::FromClosureToUserData(ref callback) => {
+                RustParamConversion::OutParam {
+                    local_variables: Vec::new(),
+                    conversion: quote! { ::core::ptr::addr_of_mut!(#callback).cast() },
+                }
+            }
+            RustConversionType::FromValueToGeneratedStruct(ref ty)
+            | RustConversionType::FromGeneratedStructToValue(ref ty) => {
+                RustParamConversion::Param {
+                    ty: ty.clone().into(),
+                    local_variables: Vec::new(),
+                    conversion: quote! { #var .into() },
+                    conversion_requires_unsafe: false,
+                }
+            }
         }
     }
 }
@@ -23,8 +23,9 @@ use autocxx_parser::{ExternCppType, IncludeCppConfig, RustFun, UnsafePolicy};
 use itertools::Itertools;
 use proc_macro2::{Span, TokenStream};
 use syn::{
-    parse_quote, punctuated::Punctuated, token::Comma, Attribute, Expr, FnArg, ForeignItem,
-    ForeignItemFn, Ident, ImplItem, Item, ItemForeignMod, ItemMod, TraitItem, Type, TypePath,
+    parse_quote, punctuated::Punctuated, token::Comma, visit_mut::VisitMut, Attribute, Expr, FnArg,
+    ForeignItem, ForeignItemFn, Ident, ImplItem, Index, Item, ItemForeignMod, ItemMod, Lifetime,
+    TraitItem, Type, TypePath,
 };
 use utils::{find_output_mod_root, generate_cxx_use_stmt};
 
@@ -42,10 +43,10 @@ use self::{
 
 use super::{
     analysis::{
-        fun::{FnPhase, PodAndDepAnalysis, ReceiverMutability},
+        fun::{FnKind, FnPhase, PodAndDepAnalysis, ReceiverMutability},
         pod::PodAnalysis,
     },
-    api::{AnalysisPhase, Api, SubclassName, TypeKind},
+    api::{AnalysisPhase, Api, Bitfield, SubclassName, TypeKind, VariantAlternative},
     convert_error::ErrorContextType,
     doc_attr::get_doc_attrs,
 };
@@ -64,7 +65,7 @@ struct ImplBlockDetails {
 }
 
 struct TraitImplBlockDetails {
-    item: TraitItem,
+    items: Vec<TraitItem>,
     key: TraitImplSignature,
 }
 
@@ -158,6 +159,8 @@ impl<'a> RsCodeGenerator<'a> {
         let subclasses_with_a_single_trivial_constructor =
             find_trivially_constructed_subclasses(&all_apis);
         let non_pod_types = find_non_pod_types(&all_apis);
+        let method_names = find_method_names(&all_apis);
+        self.remove_clashing_bitfield_accessors(&all_apis, &method_names);
         // Now let's generate the Rust code.
         let (rs_codegen_results_and_namespaces, additional_cpp_needs): (Vec<_>, Vec<_>) = all_apis
             .into_iter()
@@ -169,6 +172,7 @@ impl<'a> RsCodeGenerator<'a> {
                     &methods_by_superclass,
                     &subclasses_with_a_single_trivial_constructor,
                     &non_pod_types,
+                    &method_names,
                 );
                 ((name, gen), more_cpp_needed)
             })
@@ -325,13 +329,14 @@ impl<'a> RsCodeGenerator<'a> {
                     .or_default()
                     .push(&impl_entry.item);
             }
-            if let Some(trait_impl_entry) = &item.1.trait_impl_entry {
+            for trait_impl_entry in &item.1.trait_impl_entries {
                 trait_impl_entries_by_trait_and_ty
                     .entry(trait_impl_entry.key.clone())
                     .or_default()
-                    .push(&trait_impl_entry.item);
+                    .extend(trait_impl_entry.items.iter().cloned());
             }
         }
+        Self::complete_comparison_traits(&mut trait_impl_entries_by_trait_and_ty);
         for (ty, entries) in impl_entries_by_type.into_iter() {
             output_items.push(Item::Impl(parse_quote! {
                 impl #ty {
@@ -341,10 +346,11 @@ impl<'a> RsCodeGenerator<'a> {
         }
         for (key, entries) in trait_impl_entries_by_trait_and_ty.into_iter() {
             let unsafety = key.unsafety;
+            let generics = Self::lifetimes_for_trait_impl(&key.ty);
             let ty = key.ty;
             let trt = key.trait_signature;
             output_items.push(Item::Impl(parse_quote! {
-                #unsafety impl #trt for #ty {
+                #unsafety impl #generics #trt for #ty {
                     #(#entries)*
                 }
             }))
@@ -369,6 +375,72 @@ impl<'a> RsCodeGenerator<'a> {
         }
     }
 
+    /// Any lifetimes named in the type for which we're implementing a
+    /// trait (e.g. `&'a T`) need to be declared on the `impl`.
+    fn lifetimes_for_trait_impl(ty: &Type) -> Option<TokenStream> {
+        struct LifetimeFinder(Vec<Lifetime>);
+        impl VisitMut for LifetimeFinder {
+            fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+                if lifetime.ident != "static" && lifetime.ident != "_" && !self.0.contains(lifetime)
+                {
+                    self.0.push(lifetime.clone());
+                }
+            }
+        }
+        let mut finder = LifetimeFinder(Vec::new());
+        finder.visit_type_mut(&mut ty.clone());
+        let lifetimes = finder.0;
+        if lifetimes.is_empty() {
+            None
+        } else {
+            Some(quote! { < #(#lifetimes),* > })
+        }
+    }
+
+    /// Rust requires `PartialEq` wherever there's `PartialOrd`, and `eq`
+    /// within any `PartialEq`. C++ types may have only `operator<` or only
+    /// `operator!=`, so fill in the gaps from whatever we were given.
+    fn complete_comparison_traits(
+        trait_impl_entries: &mut HashMap<TraitImplSignature, Vec<TraitItem>>,
+    ) {
+        let partial_ords: Vec<_> = trait_impl_entries
+            .keys()
+            .filter_map(|key| {
+                comparison_trait_rhs(&key.trait_signature, "PartialOrd").map(|rhs| (key, rhs))
+            })
+            .map(|(key, rhs)| {
+                let eq_key = TraitImplSignature {
+                    trait_signature: parse_quote! { ::core::cmp::PartialEq < #rhs > },
+                    ..key.clone()
+                };
+                (eq_key, rhs)
+            })
+            .collect();
+        for (eq_key, rhs) in partial_ords {
+            trait_impl_entries.entry(eq_key).or_insert_with(|| {
+                vec![parse_quote! {
+                    fn eq(&self, other: & #rhs) -> bool {
+                        self.partial_cmp(other) == Some(::core::cmp::Ordering::Equal)
+                    }
+                }]
+            });
+        }
+        for (key, items) in trait_impl_entries.iter_mut() {
+            if let Some(rhs) = comparison_trait_rhs(&key.trait_signature, "PartialEq") {
+                if !items
+                    .iter()
+                    .any(|item| matches!(item, TraitItem::Fn(f) if f.sig.ident == "eq"))
+                {
+                    items.push(parse_quote! {
+                        fn eq(&self, other: & #rhs) -> bool {
+                            !self.ne(other)
+                        }
+                    });
+                }
+            }
+        }
+    }
+
     fn id_to_expr(id: &Ident) -> Expr {
         parse_quote! { #id }
     }
@@ -379,6 +451,7 @@ impl<'a> RsCodeGenerator<'a> {
         associated_methods: &HashMap<QualifiedName, Vec<SuperclassMethod>>,
         subclasses_with_a_single_trivial_constructor: &HashSet<QualifiedName>,
         non_pod_types: &HashSet<QualifiedName>,
+        method_names: &HashMap<QualifiedName, HashSet<String>>,
     ) -> RsCodegenResult {
         let name = api.name().clone();
         let id = name.get_final_ident();
@@ -420,7 +493,21 @@ impl<'a> RsCodeGenerator<'a> {
                 ..
             } => {
                 let doc_attrs = get_doc_attrs(&details.item.attrs);
-                self.generate_type(
+                // bindgen's accessors for bitfields are only available
+                // on bindgen's own type, i.e. if the type is POD.
+                let bitfield_getters = if kind == TypeKind::Pod
+                    && num_generics == 0
+                    && !details.bitfields.is_empty()
+                {
+                    Some(Self::generate_bitfield_getters(
+                        &name,
+                        &details.bitfields,
+                        method_names.get(&name),
+                    ))
+                } else {
+                    None
+                };
+                let mut result = self.generate_type(
                     &name,
                     id,
                     kind,
@@ -429,7 +516,9 @@ impl<'a> RsCodeGenerator<'a> {
                     || Some((Item::Struct(details.item.into()), doc_attrs)),
                     associated_methods,
                     num_generics,
-                )
+                );
+                result.output_mod_items.extend(bitfield_getters);
+                result
             }
             Api::Enum { item, .. } => {
                 let doc_attrs = get_doc_attrs(&item.attrs);
@@ -454,6 +543,12 @@ impl<'a> RsCodeGenerator<'a> {
                 associated_methods,
                 0,
             ),
+            Api::TupleStruct { elems, .. } => self.generate_tuple_struct(&name, elems),
+            Api::VariantStruct {
+                enum_name,
+                alternatives,
+                ..
+            } => self.generate_variant_struct(&name, enum_name, alternatives),
             Api::ForwardDeclaration { .. } | Api::OpaqueTypedef { .. } => self.generate_type(
                 &name,
                 id,
@@ -834,6 +929,149 @@ impl<'a> RsCodeGenerator<'a> {
         }
     }
 
+    /// Generate a struct which has the same layout as the one we generate
+    /// in C++, so that cxx can pass it by value, and conversions to and
+    /// from the Rust tuple which users actually see.
+    fn generate_tuple_struct(
+        &self,
+        name: &QualifiedName,
+        elems: Vec<crate::minisyn::Type>,
+    ) -> RsCodegenResult {
+        let id = name.get_final_ident();
+        let indices = (0..elems.len()).map(Index::from).collect_vec();
+        let tuple_ty: Type = parse_quote! { (#(#elems,)*) };
+        let mut output_mod_items = vec![
+            Item::Struct(parse_quote! {
+                #[doc(hidden)]
+                #[repr(C)]
+                pub struct #id(#(pub #elems),*);
+            }),
+            Item::Impl(parse_quote! {
+                impl From<#tuple_ty> for #id {
+                    fn from(tuple: #tuple_ty) -> Self {
+                        Self(#(tuple.#indices),*)
+                    }
+                }
+            }),
+            Item::Impl(parse_quote! {
+                impl From<#id> for #tuple_ty {
+                    fn from(tuple: #id) -> Self {
+                        (#(tuple.#indices,)*)
+                    }
+                }
+            }),
+        ];
+        output_mod_items.append(&mut self.generate_extern_type_impl(TypeKind::Pod, name));
+        RsCodegenResult {
+            extern_c_mod_items: vec![self.generate_cxxbridge_type(name, true, Vec::new())],
+            output_mod_items,
+            ..Default::default()
+        }
+    }
+
+    /// Generate a struct which has the same layout as the one we generate
+    /// in C++ for a `std::variant`, with a field for each alternative and
+    /// the index of the one which is present. Users see an enum instead.
+    fn generate_variant_struct(
+        &self,
+        name: &QualifiedName,
+        enum_name: crate::minisyn::Ident,
+        alternatives: Vec<VariantAlternative>,
+    ) -> RsCodegenResult {
+        let id = name.get_final_ident();
+        let indices = (0..alternatives.len()).collect_vec();
+        let fields = (0..alternatives.len())
+            .map(|i| make_ident(format!("a{i}")))
+            .collect_vec();
+        let variant_names = alternatives.iter().map(|alt| &alt.name).collect_vec();
+        let field_types = alternatives.iter().map(|alt| {
+            let ty = &alt.ty;
+            if alt.by_value {
+                quote! { ::core::mem::MaybeUninit<#ty> }
+            } else {
+                quote! { *mut #ty }
+            }
+        });
+        let variant_types = alternatives.iter().map(|alt| {
+            let ty = &alt.ty;
+            if alt.by_value {
+                quote! { #ty }
+            } else {
+                quote! { cxx::UniquePtr<#ty> }
+            }
+        });
+        let empty_fields = alternatives.iter().map(|alt| {
+            if alt.by_value {
+                quote! { ::core::mem::MaybeUninit::uninit() }
+            } else {
+                quote! { ::core::ptr::null_mut() }
+            }
+        });
+        let from_fields = alternatives.iter().zip(fields.iter()).map(|(alt, field)| {
+            if alt.by_value {
+                quote! { unsafe { s.#field.assume_init() } }
+            } else {
+                quote! { unsafe { cxx::UniquePtr::from_raw(s.#field) } }
+            }
+        });
+        let enum_names = std::iter::repeat(&enum_name);
+        let into_fields = alternatives.iter().map(|alt| {
+            if alt.by_value {
+                quote! { ::core::mem::MaybeUninit::new(v) }
+            } else {
+                quote! { v.into_raw() }
+            }
+        });
+        let mut output_mod_items = vec![
+            Item::Struct(parse_quote! {
+                #[doc(hidden)]
+                #[repr(C)]
+                pub struct #id {
+                    index: usize,
+                    #(#fields: #field_types),*
+                }
+            }),
+            Item::Enum(parse_quote! {
+                pub enum #enum_name {
+                    #(#variant_names(#variant_types)),*
+                }
+            }),
+            Item::Impl(parse_quote! {
+                impl From<#id> for #enum_name {
+                    fn from(s: #id) -> Self {
+                        match s.index {
+                            #(#indices => Self::#variant_names(#from_fields),)*
+                            _ => panic!("C++ std::variant was valueless"),
+                        }
+                    }
+                }
+            }),
+            Item::Impl(parse_quote! {
+                impl From<#enum_name> for #id {
+                    fn from(e: #enum_name) -> Self {
+                        let mut s = Self {
+                            index: 0,
+                            #(#fields: #empty_fields),*
+                        };
+                        match e {
+                            #(#enum_names::#variant_names(v) => {
+                                s.index = #indices;
+                                s.#fields = #into_fields;
+                            })*
+                        }
+                        s
+                    }
+                }
+            }),
+        ];
+        output_mod_items.append(&mut self.generate_extern_type_impl(TypeKind::Pod, name));
+        RsCodegenResult {
+            extern_c_mod_items: vec![self.generate_cxxbridge_type(name, true, Vec::new())],
+            output_mod_items,
+            ..Default::default()
+        }
+    }
+
     fn add_superclass_stuff_to_type(
         name: &QualifiedName,
         output_mod_items: &mut Vec<Item>,
@@ -949,6 +1187,126 @@ impl<'a> RsCodeGenerator<'a> {
         }
     }
 
+    /// If a C++ method has the same name as one of bindgen's accessors for
+    /// a bitfield, there'd be two inherent methods of that name. The C++
+    /// method wins: we remove bindgen's accessors for that bitfield, so it
+    /// can't be accessed from Rust.
+    fn remove_clashing_bitfield_accessors(
+        &mut self,
+        apis: &ApiVec<FnPhase>,
+        method_names: &HashMap<QualifiedName, HashSet<String>>,
+    ) {
+        for api in apis.iter() {
+            let (name, details) = match api {
+                Api::Struct {
+                    name,
+                    details,
+                    analysis:
+                        PodAndDepAnalysis {
+                            pod:
+                                PodAnalysis {
+                                    kind: TypeKind::Pod,
+                                    ..
+                                },
+                            ..
+                        },
+                } => (&name.name, details),
+                _ => continue,
+            };
+            let method_names = method_names.get(name);
+            let clashing_accessors: HashSet<String> = details
+                .bitfields
+                .iter()
+                .filter(|bitfield| bitfield_clashes_with_method(bitfield, method_names))
+                .flat_map(bitfield_accessor_names)
+                .collect();
+            if clashing_accessors.is_empty() {
+                continue;
+            }
+            let id = name.get_final_ident();
+            let items = match find_bindgen_mod_items(&mut self.bindgen_mod, name.get_namespace()) {
+                Some(items) => items,
+                None => continue,
+            };
+            for item in items.iter_mut() {
+                match item {
+                    Item::Impl(imp)
+                        if imp.trait_.is_none()
+                            && matches!(imp.self_ty.as_ref(), Type::Path(typ)
+                                if typ.path.segments.last().map(|seg| &seg.ident) == Some(&id)) =>
+                    {
+                        imp.items.retain(|i| {
+                            !matches!(i, ImplItem::Fn(f)
+                                if clashing_accessors.contains(&f.sig.ident.to_string()))
+                        })
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// bindgen generates a getter and a setter for each bitfield, named
+    /// `x` and `set_x`. We add a `get_x` for consistency with the setter,
+    /// unless the type already has a method of that name. Bitfields whose
+    /// accessors we removed because of clashes with C++ methods are skipped.
+    fn generate_bitfield_getters(
+        name: &QualifiedName,
+        bitfields: &[Bitfield],
+        method_names: Option<&HashSet<String>>,
+    ) -> Item {
+        let id = name.get_final_ident();
+        let bindgen_accessor_names: HashSet<String> =
+            bitfields.iter().flat_map(bitfield_accessor_names).collect();
+        let getters = bitfields.iter().filter_map(|bitfield| {
+            let getter = &bitfield.getter;
+            let get_name = format!("get_{getter}");
+            if bitfield_clashes_with_method(bitfield, method_names)
+                || bindgen_accessor_names.contains(&get_name)
+                || method_names
+                    .map(|names| names.contains(&get_name))
+                    .unwrap_or_default()
+            {
+                return None;
+            }
+            let get_id = make_ident(get_name);
+            let doc = format!("Returns the value of the bitfield `{getter}`.");
+            let ty = Self::bindgen_type_in_output_mod(name.get_namespace(), &bitfield.ty);
+            Some(quote! {
+                #[doc = #doc]
+                pub fn #get_id(&self) -> #ty {
+                    self.#getter()
+                }
+            })
+        });
+        Item::Impl(parse_quote! {
+            impl #id {
+                #(#getters)*
+            }
+        })
+    }
+
+    /// bindgen refers to its own types as `root::...`, which within
+    /// our output mod is to be found within the bindgen mod.
+    fn bindgen_type_in_output_mod(ns: &Namespace, ty: &Type) -> Type {
+        match ty {
+            Type::Path(typ)
+                if typ.qself.is_none()
+                    && typ
+                        .path
+                        .segments
+                        .first()
+                        .map(|seg| seg.ident == "root")
+                        .unwrap_or_default() =>
+            {
+                let root = find_output_mod_root(ns);
+                let segs = &typ.path.segments;
+                parse_quote! { #(#root::)* bindgen:: #segs }
+            }
+            _ => ty.clone(),
+        }
+    }
+
     fn generate_bindgen_use_stmt(name: &QualifiedName) -> Item {
         let segs = find_output_mod_root(name.get_namespace()).chain(name.get_bindgen_path_idents());
         Item::Use(parse_quote! {
@@ -1056,6 +1414,60 @@ fn find_trivially_constructed_subclasses(apis: &ApiVec<FnPhase>) -> HashSet<Qual
         .collect()
 }
 
+/// The names of the accessors bindgen generates for a bitfield.
+fn bitfield_accessor_names(bitfield: &Bitfield) -> [String; 2] {
+    let getter = bitfield.getter.to_string();
+    [format!("set_{getter}"), getter]
+}
+
+/// Whether a C++ method has the same name as one of bindgen's accessors for
+/// a bitfield.
+fn bitfield_clashes_with_method(
+    bitfield: &Bitfield,
+    method_names: Option<&HashSet<String>>,
+) -> bool {
+    method_names
+        .map(|names| {
+            bitfield_accessor_names(bitfield)
+                .iter()
+                .any(|accessor| names.contains(accessor))
+        })
+        .unwrap_or_default()
+}
+
+/// Finds the items bindgen generated for the given namespace.
+fn find_bindgen_mod_items<'a>(
+    bindgen_mod: &'a mut ItemMod,
+    ns: &Namespace,
+) -> Option<&'a mut Vec<Item>> {
+    let mut items = &mut bindgen_mod.content.as_mut()?.1;
+    for seg in std::iter::once("root").chain(ns.iter()) {
+        items = items.iter_mut().find_map(|item| match item {
+            Item::Mod(itm) if itm.ident == seg => itm.content.as_mut().map(|(_, items)| items),
+            _ => None,
+        })?;
+    }
+    Some(items)
+}
+
+/// The names of the methods we generate for each type.
+fn find_method_names(apis: &ApiVec<FnPhase>) -> HashMap<QualifiedName, HashSet<String>> {
+    let mut results: HashMap<QualifiedName, HashSet<String>> = HashMap::new();
+    for api in apis.iter() {
+        if let Api::Function { analysis, .. } = api {
+            if let FnKind::Method { impl_for, .. } = &analysis.kind {
+                if analysis.ignore_reason.is_ok() && analysis.externally_callable {
+                    results
+                        .entry(impl_for.clone())
+                        .or_default()
+                        .insert(analysis.rust_name.clone());
+                }
+            }
+        }
+    }
+    results
+}
+
 fn find_non_pod_types(apis: &ApiVec<FnPhase>) -> HashSet<QualifiedName> {
     apis.iter()
         .filter_map(|api| match api {
@@ -1099,11 +1511,28 @@ struct RsCodegenResult {
     /// Items that go in the top level.
     global_items: Vec<Item>,
     impl_entry: Option<Box<ImplBlockDetails>>,
-    trait_impl_entry: Option<Box<TraitImplBlockDetails>>,
+    trait_impl_entries: Vec<TraitImplBlockDetails>,
     /// Items that go into a per-namespace mod exposed to the user.
     output_mod_items: Vec<Item>,
 }
 
+/// If this is `::core::cmp::<trait_name><Rhs>`, returns `Rhs`.
+fn comparison_trait_rhs(trait_signature: &Type, trait_name: &str) -> Option<Type> {
+    let seg = match trait_signature {
+        Type::Path(typ) => typ.path.segments.last()?,
+        _ => return None,
+    };
+    match &seg.arguments {
+        syn::PathArguments::AngleBracketed(args) if seg.ident == trait_name => {
+            match args.args.first()? {
+                syn::GenericArgument::Type(ty) => Some(ty.clone()),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 /// An [`Item`] that always needs to be in an unsafe block.
 #[derive(Clone)]
 enum MaybeUnsafeStmt {
@@ -65,7 +65,7 @@ pub enum ConvertErrorFromCpp {
     UnsupportedType(String),
     #[error("Encountered type not yet known by autocxx: {0}")]
     UnknownType(String),
-    #[error("Encountered mutable static data, not yet supported: {0}")]
+    #[error("Encountered static data which is an array or reference, not yet supported: {0}")]
     StaticData(String),
     #[error("Encountered typedef to itself - this is a known bindgen bug: {0}")]
     InfinitelyRecursiveTypedef(QualifiedName),
@@ -75,8 +75,6 @@ pub enum ConvertErrorFromCpp {
     TemplatedTypeContainingNonPathArg(QualifiedName),
     #[error("Pointer pointed to an array, which is not yet supported")]
     InvalidArrayPointee,
-    #[error("Pointer pointed to another pointer, which is not yet supported")]
-    InvalidPointerPointee,
     #[error("Pointer pointed to something unsupported (autocxx only supports pointers to named types): {0}")]
     InvalidPointee(String),
     #[error("The 'generate' or 'generate_pod' directive for '{0}' did not result in any code being generated. Perhaps this was mis-spelled or you didn't qualify the name with any namespaces? Otherwise please report a bug.")]
@@ -109,16 +107,14 @@ pub enum ConvertErrorFromCpp {
         "This type is nested within another struct/class with protected or private visibility."
     )]
     NonPublicNestedType,
-    #[error("This function returns an rvalue reference (&&) which is not yet supported.")]
-    RValueReturn,
     #[error("This method is private")]
     PrivateMethod,
     #[error("autocxx does not know how to generate bindings to operator=")]
     AssignmentOperator,
+    #[error("This operator's first operand is a built-in type such as a primitive, so autocxx can't implement the corresponding Rust trait for it.")]
+    OperatorOnBuiltInType,
     #[error("This function was marked =delete")]
     Deleted,
-    #[error("This structure has an rvalue reference field (&&) which is not yet supported.")]
-    RValueReferenceField,
     #[error("This type was not on the allowlist, so we are not generating methods for it.")]
     MethodOfNonAllowlistedType,
     #[error("This type is templated, so we can't generate bindings. We will instead generate bindings for each instantiation.")]
@@ -135,8 +131,14 @@ pub enum ConvertErrorFromCpp {
     InvalidTypeForCppPtr(QualifiedName),
     #[error("A C++ std::vector was found containing some type that cxx can't accommodate as a vector element ({})", .0.to_cpp_name())]
     InvalidTypeForCppVector(QualifiedName),
-    #[error("Variadic functions are not supported by cxx or autocxx.")]
+    #[error("Variadic functions are not supported by cxx. For a free function, use variadic! to declare fixed-arity instantiations of it.")]
     Variadic,
+    #[error("This function was listed in a throws! directive, but autocxx can only report exceptions from ordinary functions and methods, not from constructors, destructors or other special members.")]
+    ThrowingSpecialMember,
+    #[error("Parameter {0} was listed in an out_param! directive, but autocxx can only return out-params from ordinary functions and methods, and only if they're a pointer to a mutable pointer to a type which can be held in a UniquePtr.")]
+    InvalidOutParam(String),
+    #[error("Parameter {0} was listed in a callback! directive, but autocxx can only accept a closure for a C function pointer whose parameters include a void* of user data, and only if the other parameter named in the directive is a void*.")]
+    InvalidCallback(String),
     #[error("A type had a template inside a std::vector, which is not supported.")]
     GenericsWithinVector,
     #[error("This typedef takes generic parameters, not yet supported by autocxx.")]
@@ -99,6 +99,24 @@ pub(crate) fn convert_apis<FF, SF, EF, TF, A, B>(
                 rs_definition,
                 cpp_definition,
             }))),
+            Api::TupleStruct {
+                name,
+                elems,
+                cpp_definition,
+            } => Ok(Box::new(std::iter::once(Api::TupleStruct {
+                name,
+                elems,
+                cpp_definition,
+            }))),
+            Api::VariantStruct {
+                name,
+                enum_name,
+                alternatives,
+            } => Ok(Box::new(std::iter::once(Api::VariantStruct {
+                name,
+                enum_name,
+                alternatives,
+            }))),
             Api::ForwardDeclaration { name, err } => {
                 Ok(Box::new(std::iter::once(Api::ForwardDeclaration {
                     name,
@@ -31,7 +31,8 @@ use itertools::Itertools;
 use syn::{Item, ItemMod};
 
 use crate::{
-    types::QualifiedName, CodegenOptions, CppFilePair, ParseCallbackResults, UnsafePolicy,
+    types::{CppOperator, QualifiedName},
+    CodegenOptions, CppFilePair, ParseCallbackResults, UnsafePolicy,
 };
 
 use self::{
@@ -40,12 +41,18 @@ use self::{
         allocators::create_alloc_and_frees,
         casts::add_casts,
         check_names,
+        concrete_methods::create_concrete_methods,
         constructor_deps::decorate_types_with_constructor_deps,
+        field_accessors::create_field_accessors,
         gc::filter_apis_by_following_edges_from_allowlist,
+        hashes::create_hash_functions,
+        iterators::create_iterator_functions,
+        maps::create_map_functions,
         pod::analyze_pod_apis,
         remove_ignored::filter_apis_by_ignored_dependents,
         replace_hopeless_typedef_targets,
         tdef::convert_typedef_targets,
+        variadics::create_variadic_instantiations,
     },
     api::AnalysisPhase,
     apivec::ApiVec,
@@ -141,6 +148,12 @@ impl<'a> BridgeConverter<'a> {
                 let analyzed_apis = replace_hopeless_typedef_targets(self.config, analyzed_apis);
                 let analyzed_apis = add_casts(analyzed_apis);
                 let analyzed_apis = create_alloc_and_frees(analyzed_apis);
+                let analyzed_apis = create_hash_functions(analyzed_apis, self.config);
+                let analyzed_apis = create_iterator_functions(analyzed_apis, self.config);
+                let analyzed_apis = create_map_functions(analyzed_apis, self.config);
+                let analyzed_apis = create_field_accessors(analyzed_apis, self.config);
+                let analyzed_apis = create_concrete_methods(analyzed_apis, self.config);
+                let analyzed_apis = create_variadic_instantiations(analyzed_apis, self.config);
                 // Next, figure out how we materialize different functions.
                 // Some will be simple entries in the cxx::bridge module; others will
                 // require C++ wrapper functions. This is probably the most complex
@@ -264,6 +277,11 @@ impl CppEffectiveName {
         Self(rust_name)
     }
 
+    /// The name by which C++ code can call an operator, e.g. `operator+`.
+    fn from_cpp_operator(op: CppOperator) -> CppEffectiveName {
+        Self(op.cpp_name().to_string())
+    }
+
     fn from_fully_qualified_name_for_subclass(to_cpp_name: &str) -> CppEffectiveName {
         Self(to_cpp_name.to_string())
     }
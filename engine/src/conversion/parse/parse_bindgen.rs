@@ -11,16 +11,22 @@ use indexmap::set::IndexSet as HashSet;
 
 use crate::{
     conversion::{
-        api::{Api, ApiName, NullPhase, StructDetails, SubclassName, TypedefKind, UnanalyzedApi},
+        api::{
+            Api, ApiName, Bitfield, NullPhase, StructDetails, SubclassName, TypedefKind,
+            UnanalyzedApi,
+        },
         apivec::ApiVec,
         check_for_fatal_attrs,
         convert_error::LocatedConvertErrorFromRust,
-        type_helpers::type_is_reference,
+        type_helpers::{
+            extract_bitfield_unit_storage, extract_manually_drop_type,
+            mark_as_anonymous_union_member, type_is_reference,
+        },
         utilities::generate_utilities,
         ConvertError, ConvertErrorFromCpp,
     },
     known_types, minisyn,
-    types::{Namespace, QualifiedName},
+    types::{bindgen_name_for_cpp_operator, Namespace, QualifiedName},
     ParseCallbackResults,
 };
 use crate::{
@@ -31,7 +37,11 @@ use crate::{
     types::validate_ident_ok_for_cxx,
 };
 use autocxx_parser::{IncludeCppConfig, RustPath};
-use syn::{parse_quote, Fields, Ident, Item, Type, TypePath, UseTree};
+use syn::{
+    parse_quote, punctuated::Punctuated, token::Comma, Field, Fields, FieldsNamed, FnArg, Ident,
+    ImplItem, Item, ItemStruct, ItemUnion, ReturnType, Signature, Type, TypePath, UseTree,
+    Visibility,
+};
 
 use super::parse_foreign_mod::ParseForeignMod;
 
@@ -192,23 +202,124 @@ impl<'a> ParseBindgen<'a> {
     fn parse_mod_items(&mut self, items: Option<&Vec<Item>>, ns: Namespace) {
         // This object maintains some state specific to this namespace, i.e.
         // this particular mod.
-        let mut mod_converter = ParseForeignMod::new(ns.clone(), self.parse_callback_results);
-        let mut more_apis = ApiVec::new();
         let empty_vec = vec![];
-        for item in items.unwrap_or(&empty_vec) {
+        let items = items.unwrap_or(&empty_vec);
+        let mut mod_converter = ParseForeignMod::new(
+            ns.clone(),
+            Self::find_type_names(items),
+            self.parse_callback_results,
+            self.config,
+        );
+        let mut more_apis = ApiVec::new();
+        let anonymous_members = Self::find_anonymous_members(items);
+        let bitfields = Self::find_bitfields(items);
+        for item in items {
             report_any_error(&ns, &mut more_apis, || {
-                self.parse_item(item, &mut mod_converter, &ns)
+                self.parse_item(
+                    item,
+                    &mut mod_converter,
+                    &ns,
+                    &anonymous_members,
+                    &bitfields,
+                )
             });
         }
         self.apis.append(&mut more_apis);
         mod_converter.finished(&mut self.apis);
     }
 
+    /// Finds the names of the structs and unions within this mod.
+    fn find_type_names(items: &[Item]) -> HashSet<Ident> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Struct(ItemStruct { ident, .. }) | Item::Union(ItemUnion { ident, .. }) => {
+                    Some(ident.clone())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Finds the types which bindgen made up for anonymous unions and
+    /// structs within this mod, their fields, and whether each is a union.
+    fn find_anonymous_members(items: &[Item]) -> HashMap<Ident, (FieldsNamed, bool)> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Struct(ItemStruct {
+                    ident,
+                    fields: Fields::Named(fields),
+                    ..
+                }) if ident.to_string().contains("__bindgen_ty_") => {
+                    Some((ident.clone(), (fields.clone(), false)))
+                }
+                Item::Union(ItemUnion { ident, fields, .. })
+                    if ident.to_string().contains("__bindgen_ty_") =>
+                {
+                    Some((ident.clone(), (fields.clone(), true)))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Finds the public bitfields within this mod, by looking for the
+    /// accessors which bindgen generates for them. They're the only safe
+    /// methods bindgen generates, and each getter has a setter called `set_`
+    /// followed by its name.
+    fn find_bitfields(items: &[Item]) -> HashMap<Ident, Vec<Bitfield>> {
+        let mut bitfields: HashMap<Ident, Vec<Bitfield>> = HashMap::new();
+        for item in items {
+            let imp = match item {
+                Item::Impl(imp) if imp.trait_.is_none() => imp,
+                _ => continue,
+            };
+            let ty_id = match imp.self_ty.as_ref() {
+                Type::Path(typ) => match typ.path.segments.last() {
+                    Some(seg) => seg.ident.clone(),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            let safe_fns: HashMap<String, &Signature> = imp
+                .items
+                .iter()
+                .filter_map(|i| match i {
+                    ImplItem::Fn(f)
+                        if f.sig.unsafety.is_none() && matches!(f.vis, Visibility::Public(_)) =>
+                    {
+                        Some((f.sig.ident.to_string(), &f.sig))
+                    }
+                    _ => None,
+                })
+                .collect();
+            for (name, sig) in &safe_fns {
+                let takes_only_ref_self = sig.inputs.len() == 1
+                    && match sig.inputs.first() {
+                        Some(FnArg::Receiver(r)) => r.reference.is_some() && r.mutability.is_none(),
+                        _ => false,
+                    };
+                if let ReturnType::Type(_, ty) = &sig.output {
+                    if takes_only_ref_self && safe_fns.contains_key(&format!("set_{name}")) {
+                        bitfields.entry(ty_id.clone()).or_default().push(Bitfield {
+                            getter: sig.ident.clone().into(),
+                            ty: ty.as_ref().clone().into(),
+                        });
+                    }
+                }
+            }
+        }
+        bitfields
+    }
+
     fn parse_item(
         &mut self,
         item: &Item,
         mod_converter: &mut ParseForeignMod,
         ns: &Namespace,
+        anonymous_members: &HashMap<Ident, (FieldsNamed, bool)>,
+        bitfields: &HashMap<Ident, Vec<Bitfield>>,
     ) -> Result<(), ConvertErrorWithContext> {
         match item {
             Item::ForeignMod(fm) => {
@@ -216,59 +327,23 @@ impl<'a> ParseBindgen<'a> {
                 Ok(())
             }
             Item::Struct(s) => {
-                if s.ident.to_string().ends_with("__bindgen_vtable") {
-                    return Ok(());
-                }
-                // cxx::bridge can't cope with type aliases to generic
-                // types at the moment.
-                let name = api_name_qualified(ns, s.ident.clone(), self.parse_callback_results)?;
-                if known_types().is_known_subtitute_type(&name.name) {
-                    // This is one of the replacement types, e.g.
-                    // root::Str replacing rust::Str or
-                    // root::string replacing root::std::string
-                    return Ok(());
-                }
-                let mut err = check_for_fatal_attrs(self.parse_callback_results, &name.name).err();
-                let api = if (ns.is_empty() && self.config.is_rust_type(&s.ident))
-                    || known_types().is_known_type(&name.name)
-                {
-                    None
-                } else if Self::spot_forward_declaration(&s.fields)
-                    || (Self::spot_zero_length_struct(&s.fields) && err.is_some())
-                {
-                    // Forward declarations are recorded especially because we can't
-                    // store them in UniquePtr or similar.
-                    // Templated forward declarations don't appear with an _unused field (which is what
-                    // we spot in the previous clause) but instead with an _address field.
-                    // So, solely in the case where we're storing up an error about such
-                    // a templated type, we'll also treat such cases as forward declarations.
-                    //
-                    // We'll also at this point check for one specific problem with
-                    // forward declarations.
-                    if err.is_none() && name.cpp_name().is_nested() {
-                        err = Some(ConvertErrorWithContext(
-                            ConvertErrorFromCpp::ForwardDeclaredNestedType,
-                            Some(ErrorContext::new_for_item(s.ident.clone().into())),
-                        ));
-                    }
-                    Some(UnanalyzedApi::ForwardDeclaration { name, err })
-                } else {
-                    let has_rvalue_reference_fields = Self::spot_rvalue_reference_fields(&s.fields);
-                    Some(UnanalyzedApi::Struct {
-                        name,
-                        details: Box::new(StructDetails {
-                            item: s.clone().into(),
-                            has_rvalue_reference_fields,
-                        }),
-                        analysis: (),
-                    })
+                let item = Self::prepare_for_analysis(s.clone(), anonymous_members);
+                let bitfields = bitfields.get(&s.ident).cloned().unwrap_or_default();
+                self.parse_struct(item, false, bitfields, ns)
+            }
+            Item::Union(u) => {
+                let item = ItemStruct {
+                    attrs: u.attrs.clone(),
+                    vis: u.vis.clone(),
+                    struct_token: Default::default(),
+                    ident: u.ident.clone(),
+                    generics: u.generics.clone(),
+                    fields: Fields::Named(u.fields.clone()),
+                    semi_token: None,
                 };
-                if let Some(api) = api {
-                    if !self.config.is_on_blocklist(&api.name().to_cpp_name()) {
-                        self.apis.push(api);
-                    }
-                }
-                Ok(())
+                let item = Self::prepare_for_analysis(item, anonymous_members);
+                let bitfields = bitfields.get(&u.ident).cloned().unwrap_or_default();
+                self.parse_struct(item, true, bitfields, ns)
             }
             Item::Enum(e) => {
                 let api = UnanalyzedApi::Enum {
@@ -386,6 +461,133 @@ impl<'a> ParseBindgen<'a> {
         }
     }
 
+    /// Parses a struct, or a union, which for our purposes is a struct
+    /// with the same fields.
+    fn parse_struct(
+        &mut self,
+        s: ItemStruct,
+        is_union: bool,
+        bitfields: Vec<Bitfield>,
+        ns: &Namespace,
+    ) -> Result<(), ConvertErrorWithContext> {
+        if s.ident.to_string().ends_with("__bindgen_vtable") {
+            return Ok(());
+        }
+        // cxx::bridge can't cope with type aliases to generic
+        // types at the moment.
+        let name = api_name_qualified(ns, s.ident.clone(), self.parse_callback_results)?;
+        if known_types().is_known_subtitute_type(&name.name) {
+            // This is one of the replacement types, e.g.
+            // root::Str replacing rust::Str or
+            // root::string replacing root::std::string
+            return Ok(());
+        }
+        let mut err = check_for_fatal_attrs(self.parse_callback_results, &name.name).err();
+        let api = if (ns.is_empty() && self.config.is_rust_type(&s.ident))
+            || known_types().is_known_type(&name.name)
+        {
+            None
+        } else if Self::spot_forward_declaration(&s.fields)
+            || (Self::spot_zero_length_struct(&s.fields) && err.is_some())
+        {
+            // Forward declarations are recorded especially because we can't
+            // store them in UniquePtr or similar.
+            // Templated forward declarations don't appear with an _unused field (which is what
+            // we spot in the previous clause) but instead with an _address field.
+            // So, solely in the case where we're storing up an error about such
+            // a templated type, we'll also treat such cases as forward declarations.
+            //
+            // We'll also at this point check for one specific problem with
+            // forward declarations.
+            if err.is_none() && name.cpp_name().is_nested() {
+                err = Some(ConvertErrorWithContext(
+                    ConvertErrorFromCpp::ForwardDeclaredNestedType,
+                    Some(ErrorContext::new_for_item(s.ident.clone().into())),
+                ));
+            }
+            Some(UnanalyzedApi::ForwardDeclaration { name, err })
+        } else {
+            let has_rvalue_reference_fields = Self::spot_rvalue_reference_fields(&s.fields);
+            Some(UnanalyzedApi::Struct {
+                name,
+                details: Box::new(StructDetails {
+                    item: s.into(),
+                    has_rvalue_reference_fields,
+                    is_union,
+                    bitfields,
+                }),
+                analysis: (),
+            })
+        };
+        if let Some(api) = api {
+            if !self.config.is_on_blocklist(&api.name().to_cpp_name()) {
+                self.apis.push(api);
+            }
+        }
+        Ok(())
+    }
+
+    /// C++ lets the members of an anonymous union or struct be used as if
+    /// they were members of the enclosing type, so that's how our analysis
+    /// should see them. bindgen instead gives the enclosing type a field
+    /// called `__bindgen_anon_N`, whose type it names itself. We also look
+    /// through the `ManuallyDrop` which bindgen puts around each member of
+    /// a union, and treat the storage for any bitfields as the bytes it is.
+    fn prepare_for_analysis(
+        mut s: ItemStruct,
+        anonymous_members: &HashMap<Ident, (FieldsNamed, bool)>,
+    ) -> ItemStruct {
+        if let Fields::Named(fields) = &mut s.fields {
+            fields.named = Self::flatten_fields(&fields.named, anonymous_members, false);
+        }
+        s
+    }
+
+    /// Any fields within anonymous unions are marked as such, since only
+    /// one of them is valid at a time.
+    fn flatten_fields(
+        fields: &Punctuated<Field, Comma>,
+        anonymous_members: &HashMap<Ident, (FieldsNamed, bool)>,
+        within_union: bool,
+    ) -> Punctuated<Field, Comma> {
+        let mut flattened = Punctuated::new();
+        for f in fields {
+            let is_anonymous_member = f
+                .ident
+                .as_ref()
+                .map(|id| id.to_string().starts_with("__bindgen_anon_"))
+                .unwrap_or_default();
+            let anonymous_type = match &f.ty {
+                Type::Path(typ) if is_anonymous_member => typ
+                    .path
+                    .segments
+                    .last()
+                    .and_then(|seg| anonymous_members.get(&seg.ident)),
+                _ => None,
+            };
+            match anonymous_type {
+                Some((inner, is_union)) => flattened.extend(Self::flatten_fields(
+                    &inner.named,
+                    anonymous_members,
+                    within_union || *is_union,
+                )),
+                None => {
+                    let mut f = f.clone();
+                    if within_union {
+                        mark_as_anonymous_union_member(&mut f);
+                    }
+                    if let Some(ty) = extract_manually_drop_type(&f.ty)
+                        .or_else(|| extract_bitfield_unit_storage(&f.ty))
+                    {
+                        f.ty = ty.clone();
+                    }
+                    flattened.push(f);
+                }
+            }
+        }
+        flattened
+    }
+
     fn spot_forward_declaration(s: &Fields) -> bool {
         Self::spot_field(s, "_unused")
     }
@@ -411,7 +613,11 @@ impl<'a> ParseBindgen<'a> {
             .map(|api| api.name().to_cpp_name())
             .collect();
         for generate_directive in self.config.must_generate_list() {
-            if !api_names.contains(&generate_directive) {
+            // Operators are known to us by the names under which bindgen
+            // reported them.
+            let name_to_find = bindgen_name_for_cpp_operator(&generate_directive)
+                .unwrap_or_else(|| generate_directive.clone());
+            if !api_names.contains(&name_to_find) {
                 return Err(ConvertErrorFromCpp::DidNotGenerateAnything(
                     generate_directive,
                 ));
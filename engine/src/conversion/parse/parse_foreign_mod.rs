@@ -6,24 +6,34 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::conversion::api::{ApiName, NullPhase, Provenance};
+use crate::conversion::analysis::fun::function_wrapper::{CppFunctionBody, CppFunctionKind};
+use crate::conversion::api::{ApiName, CppVisibility, NullPhase, Provenance};
 use crate::conversion::apivec::ApiVec;
 use crate::conversion::doc_attr::get_doc_attrs;
 use crate::conversion::error_reporter::report_any_error;
+use crate::conversion::type_helpers::{type_is_reference, unwrap_reference};
 use crate::conversion::{
-    api::{FuncToConvert, UnanalyzedApi},
+    api::{FuncToConvert, TraitSynthesis, UnanalyzedApi},
     convert_error::ConvertErrorWithContext,
     convert_error::ErrorContext,
 };
 use crate::minisyn::{minisynize_punctuated, minisynize_vec};
-use crate::types::strip_bindgen_original_suffix_from_ident;
+use crate::types::{make_ident, strip_bindgen_original_suffix_from_ident, CppOperator};
 use crate::ParseCallbackResults;
 use crate::{
     conversion::ConvertErrorFromCpp,
     types::{Namespace, QualifiedName},
 };
+use autocxx_parser::IncludeCppConfig;
+use indexmap::set::IndexSet as HashSet;
 use std::collections::HashMap;
-use syn::{Block, Expr, ExprCall, ForeignItem, Ident, ImplItem, ItemImpl, Stmt, Type};
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{
+    parse_quote, Block, Expr, ExprCall, ExprLit, FnArg, ForeignItem, ForeignItemStatic, Ident,
+    ImplItem, ItemImpl, Lit, Meta, MetaNameValue, ReturnType, Signature, StaticMutability, Stmt,
+    Type,
+};
 
 /// Parses a given bindgen-generated 'mod' into suitable
 /// [Api]s. In bindgen output, a given mod concerns
@@ -40,18 +50,31 @@ pub(crate) struct ParseForeignMod<'a> {
     // may actually be methods (static or otherwise). Mapping from
     // function name to type name.
     method_receivers: HashMap<Ident, QualifiedName>,
+    // The structs and unions in this mod, so that we can spot which
+    // static data belongs to a class.
+    type_names: HashSet<Ident>,
+    static_data_accessors: ApiVec<NullPhase>,
     ignored_apis: ApiVec<NullPhase>,
     parse_callback_results: &'a ParseCallbackResults,
+    config: &'a IncludeCppConfig,
 }
 
 impl<'a> ParseForeignMod<'a> {
-    pub(crate) fn new(ns: Namespace, parse_callback_results: &'a ParseCallbackResults) -> Self {
+    pub(crate) fn new(
+        ns: Namespace,
+        type_names: HashSet<Ident>,
+        parse_callback_results: &'a ParseCallbackResults,
+        config: &'a IncludeCppConfig,
+    ) -> Self {
         Self {
             ns,
             funcs_to_convert: Vec::new(),
             method_receivers: HashMap::new(),
+            type_names,
+            static_data_accessors: ApiVec::new(),
             ignored_apis: ApiVec::new(),
             parse_callback_results,
+            config,
         }
     }
 
@@ -73,30 +96,52 @@ impl<'a> ParseForeignMod<'a> {
                 let doc_attrs = get_doc_attrs(&item.attrs);
                 let unsuffixed_name = strip_bindgen_original_suffix_from_ident(&item.sig.ident);
                 let qn = QualifiedName::new(&self.ns, unsuffixed_name.clone().into());
+                let original_name = self.parse_callback_results.get_fn_original_name(&qn);
+                // C++ operators may be able to become Rust trait implementations.
+                let cpp_operator = original_name.as_ref().and_then(|name| name.cpp_operator());
+                let add_to_trait = cpp_operator.map(TraitSynthesis::Operator);
+                let streaming_inputs = match cpp_operator {
+                    Some(CppOperator::Shl) => streaming_operator_inputs(&item.sig),
+                    _ => None,
+                };
+                // We ask bindgen for every streaming operator in each
+                // namespace we're interested in, so only keep those which
+                // print a type the user asked for.
+                if let Some(inputs) = &streaming_inputs {
+                    if !self.is_streamable(inputs) {
+                        return Ok(());
+                    }
+                }
+                let (inputs, output, synthetic_cpp) = streaming_inputs
+                    .map(|inputs| {
+                        (
+                            inputs,
+                            parse_quote! { -> root::std::string },
+                            Some((CppFunctionBody::StreamToString, CppFunctionKind::Function)),
+                        )
+                    })
+                    .unwrap_or_else(|| (item.sig.inputs.clone(), item.sig.output.clone(), None));
                 self.funcs_to_convert.push(FuncToConvert {
                     provenance: Provenance::Bindgen,
                     self_ty: None,
                     ident: unsuffixed_name.clone().into(),
                     doc_attrs: minisynize_vec(doc_attrs),
-                    inputs: minisynize_punctuated(item.sig.inputs.clone()),
-                    output: item.sig.output.clone().into(),
+                    inputs: minisynize_punctuated(inputs),
+                    output: output.into(),
                     vis: item.vis.clone().into(),
                     virtualness: self.parse_callback_results.get_virtualness(&qn),
                     cpp_vis: self.parse_callback_results.get_cpp_visibility(&qn),
                     special_member: self.parse_callback_results.special_member_kind(&qn),
-                    original_name: self.parse_callback_results.get_fn_original_name(&qn),
+                    original_name,
                     synthesized_this_type: None,
-                    add_to_trait: None,
+                    add_to_trait,
                     is_deleted: self.parse_callback_results.get_deleted_or_defaulted(&qn),
-                    synthetic_cpp: None,
+                    synthetic_cpp,
                     variadic: item.sig.variadic.is_some(),
                 });
                 Ok(())
             }
-            ForeignItem::Static(item) => Err(ConvertErrorWithContext(
-                ConvertErrorFromCpp::StaticData(item.ident.to_string()),
-                Some(ErrorContext::new_for_item(item.ident.clone().into())),
-            )),
+            ForeignItem::Static(item) => self.parse_static(item),
             _ => Err(ConvertErrorWithContext(
                 ConvertErrorFromCpp::UnexpectedForeignItem,
                 None,
@@ -104,6 +149,176 @@ impl<'a> ParseForeignMod<'a> {
         }
     }
 
+    /// Whether the operand of a streaming operator is a type on the
+    /// allowlist.
+    fn is_streamable(&self, inputs: &Punctuated<FnArg, Comma>) -> bool {
+        let ty = match inputs.first() {
+            Some(FnArg::Typed(pt)) => match pt.ty.as_ref() {
+                Type::Path(typ) => match unwrap_reference(typ, false) {
+                    Some(ptr) => ptr.elem.as_ref(),
+                    None => pt.ty.as_ref(),
+                },
+                _ => return false,
+            },
+            _ => return false,
+        };
+        let name = match ty {
+            // bindgen may refer to types in the same namespace without
+            // qualification.
+            Type::Path(typ) if typ.path.segments.len() == 1 => {
+                QualifiedName::new(&self.ns, typ.path.segments[0].ident.clone().into())
+            }
+            Type::Path(typ) => QualifiedName::from_type_path(typ),
+            _ => return false,
+        }
+        .to_cpp_name();
+        self.config.is_on_allowlist(&name) || self.config.get_pod_requests().contains(&name)
+    }
+
+    /// cxx can't refer to C++ variables, so for each global variable or
+    /// static data member we make C++ functions which access it instead.
+    /// These are methods of the class, if it's a static data member.
+    fn parse_static(&mut self, item: &ForeignItemStatic) -> Result<(), ConvertErrorWithContext> {
+        let ty = item.ty.as_ref();
+        if matches!(ty, Type::Array(_))
+            || type_is_reference(ty, false)
+            || type_is_reference(ty, true)
+        {
+            return Err(ConvertErrorWithContext(
+                ConvertErrorFromCpp::StaticData(item.ident.to_string()),
+                Some(ErrorContext::new_for_item(item.ident.clone().into())),
+            ));
+        }
+        let (self_ty, var) = self.find_static_variable(item).ok_or_else(|| {
+            ConvertErrorWithContext(
+                ConvertErrorFromCpp::StaticData(item.ident.to_string()),
+                Some(ErrorContext::new_for_item(item.ident.clone().into())),
+            )
+        })?;
+        let member_name = var.get_final_item();
+        let is_mut = matches!(item.mutability, StaticMutability::Mut(_));
+        let mut accessors: Vec<(String, String, ReturnType, CppFunctionBody)> = Vec::new();
+        // Anything defined by the C++ might not be copyable, so we
+        // only offer to return simple types by value.
+        let is_simple = match ty {
+            Type::Ptr(_) => true,
+            Type::Path(typ) => typ
+                .path
+                .segments
+                .first()
+                .is_some_and(|seg| seg.ident != "root"),
+            _ => false,
+        };
+        if is_simple {
+            accessors.push((
+                format!("{}_autocxx_get", item.ident),
+                format!("get_{member_name}"),
+                parse_quote! { -> #ty },
+                CppFunctionBody::StaticData(var.clone()),
+            ));
+        }
+        // This one is named after the variable itself so that
+        // `generate!` directives for it are seen to be obeyed.
+        accessors.push((
+            item.ident.to_string(),
+            format!("{member_name}_ref"),
+            parse_quote! { -> *const #ty },
+            CppFunctionBody::StaticDataAddress(var.clone()),
+        ));
+        if is_mut {
+            accessors.push((
+                format!("{}_autocxx_mut", item.ident),
+                format!("{member_name}_mut"),
+                parse_quote! { -> *mut #ty },
+                CppFunctionBody::StaticDataAddress(var),
+            ));
+        }
+        let doc_attrs = minisynize_vec(get_doc_attrs(&item.attrs));
+        for (api_name, fn_name, output, body) in accessors {
+            self.static_data_accessors.push(UnanalyzedApi::Function {
+                name: ApiName::new_from_qualified_name(QualifiedName::new(
+                    &self.ns,
+                    make_ident(api_name),
+                )),
+                fun: Box::new(FuncToConvert {
+                    provenance: Provenance::SynthesizedOther,
+                    self_ty: self_ty.clone(),
+                    ident: make_ident(fn_name),
+                    doc_attrs: doc_attrs.clone(),
+                    inputs: Punctuated::new(),
+                    output: output.into(),
+                    vis: parse_quote! { pub },
+                    virtualness: None,
+                    cpp_vis: CppVisibility::Public,
+                    special_member: None,
+                    original_name: None,
+                    synthesized_this_type: None,
+                    add_to_trait: None,
+                    is_deleted: None,
+                    synthetic_cpp: Some((body, CppFunctionKind::Function)),
+                    variadic: false,
+                }),
+                analysis: (),
+            });
+        }
+        Ok(())
+    }
+
+    /// bindgen names a static data member after its class, followed by an
+    /// underscore and the name of the member, which is indistinguishable
+    /// from a global variable of that name. So we look at the symbol which
+    /// bindgen gives in `link_name`, whose mangling records the scope of
+    /// the variable. Returns the class, if this is such a member, and the
+    /// C++ name of the variable, or `None` if we can't tell.
+    /// Each translation unit has its own copy of a variable with internal
+    /// linkage, e.g. one declared `static` in a header, so our C++ couldn't
+    /// see changes made elsewhere. That's fine for constants, but we also
+    /// return `None` for any other such variable.
+    fn find_static_variable(
+        &self,
+        item: &ForeignItemStatic,
+    ) -> Option<(Option<QualifiedName>, QualifiedName)> {
+        // Variables without a `link_name` are known to the linker by
+        // their own name, which can't be true of a member.
+        let symbol = match get_link_name(item) {
+            None => {
+                return Some((
+                    None,
+                    QualifiedName::new(&self.ns, make_ident(item.ident.to_string())),
+                ))
+            }
+            Some(symbol) => symbol,
+        };
+        let (mut scope, internal_linkage) = demangle_variable_scope(&symbol)?;
+        if internal_linkage && matches!(item.mutability, StaticMutability::Mut(_)) {
+            return None;
+        }
+        let member = scope.pop()?;
+        let mut scope = scope.into_iter();
+        for ns_segment in self.ns.iter() {
+            if scope.next()? != ns_segment {
+                return None;
+            }
+        }
+        let classes = scope.collect::<Vec<_>>();
+        if classes.is_empty() {
+            return Some((None, QualifiedName::new(&self.ns, make_ident(member))));
+        }
+        // bindgen names nested classes after their enclosing class.
+        let class = classes.join("_");
+        if !self.type_names.iter().any(|id| id == &class) {
+            return None;
+        }
+        let self_ty = QualifiedName::new(&self.ns, make_ident(&class));
+        let cpp_class = self
+            .parse_callback_results
+            .get_original_name(&self_ty)
+            .map(|name| name.for_original_name_map().to_string())
+            .unwrap_or(class);
+        let var = QualifiedName::new(&self.ns.push(cpp_class), make_ident(member));
+        Some((Some(self_ty), var))
+    }
+
     /// Record information from impl blocks encountered in bindgen
     /// output.
     pub(crate) fn convert_impl_items(&mut self, imp: ItemImpl) {
@@ -132,6 +347,7 @@ impl<'a> ParseForeignMod<'a> {
     /// the resulting APIs.
     pub(crate) fn finished(mut self, apis: &mut ApiVec<NullPhase>) {
         apis.append(&mut self.ignored_apis);
+        apis.append(&mut self.static_data_accessors);
         while !self.funcs_to_convert.is_empty() {
             let mut fun = self.funcs_to_convert.remove(0);
             fun.self_ty = self.method_receivers.get(&fun.ident).cloned();
@@ -162,9 +378,109 @@ fn get_called_function(block: &Block) -> Option<&Ident> {
     }
 }
 
+/// `std::ostream& operator<<(std::ostream&, const T&)` is the usual way to
+/// make a C++ type printable. If this is one of those, returns the inputs
+/// of a function which instead streams a `T` into a `std::string`, which
+/// we can use for `Display`.
+fn streaming_operator_inputs(sig: &Signature) -> Option<Punctuated<FnArg, Comma>> {
+    if sig.inputs.len() != 2 {
+        return None;
+    }
+    let stream_is_ostream = match sig.inputs.first() {
+        Some(FnArg::Typed(pt)) => match pt.ty.as_ref() {
+            Type::Path(typ) => matches!(unwrap_reference(typ, false),
+                Some(ptr) if matches!(ptr.elem.as_ref(), Type::Path(elem)
+                    if elem.path.segments.last().is_some_and(|seg| seg.ident == "ostream"))),
+            _ => false,
+        },
+        _ => false,
+    };
+    if !stream_is_ostream {
+        return None;
+    }
+    Some(sig.inputs.iter().skip(1).cloned().collect())
+}
+
+/// Returns the symbol given in any `#[link_name]` attribute.
+fn get_link_name(item: &ForeignItemStatic) -> Option<String> {
+    item.attrs.iter().find_map(|attr| match &attr.meta {
+        Meta::NameValue(MetaNameValue {
+            path,
+            value:
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(symbol),
+                    ..
+                }),
+            ..
+        }) if path.is_ident("link_name") => {
+            // bindgen prefixes the symbol to stop LLVM mangling it further.
+            Some(symbol.value().trim_start_matches('\u{1}').to_string())
+        }
+        _ => None,
+    })
+}
+
+/// Finds the fully-qualified name of a variable from its mangled symbol,
+/// for either the Itanium or Microsoft C++ ABI, and whether it has internal
+/// linkage. Returns `None` for anything more complex than a series of plain
+/// identifiers, e.g. templates.
+fn demangle_variable_scope(symbol: &str) -> Option<(Vec<String>, bool)> {
+    // Apple platforms add an extra leading underscore.
+    let symbol = if symbol.starts_with("__Z") {
+        &symbol[1..]
+    } else {
+        symbol
+    };
+    if let Some(mut rest) = symbol.strip_prefix("_Z") {
+        // e.g. `_ZN1N6Config7versionE`, or `_ZL7counter` for a variable
+        // with internal linkage.
+        let nested = rest.starts_with('N');
+        if nested {
+            rest = &rest[1..];
+        }
+        let mut scope = Vec::new();
+        let mut internal_linkage = false;
+        loop {
+            if let Some(after_l) = rest.strip_prefix('L') {
+                internal_linkage = true;
+                rest = after_l;
+            }
+            let len_digits = rest.chars().take_while(char::is_ascii_digit).count();
+            if len_digits == 0 {
+                break;
+            }
+            let len: usize = rest[..len_digits].parse().ok()?;
+            let segment = rest.get(len_digits..len_digits + len)?;
+            scope.push(segment.to_string());
+            rest = &rest[len_digits + len..];
+            if !nested {
+                break;
+            }
+        }
+        let finished = if nested { rest == "E" } else { rest.is_empty() };
+        (finished && !scope.is_empty()).then_some((scope, internal_linkage))
+    } else if let Some(rest) = symbol.strip_prefix('?') {
+        // e.g. `?version@Config@N@@2HA`, innermost scope first. Variables
+        // with internal linkage get no special mangling.
+        let (name, _) = rest.split_once("@@")?;
+        let scope: Vec<String> = name.split('@').rev().map(str::to_string).collect();
+        scope
+            .iter()
+            .all(|segment| {
+                segment
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            })
+            .then_some((scope, false))
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::get_called_function;
+    use super::{demangle_variable_scope, get_called_function};
     use syn::parse_quote;
     use syn::Block;
 
@@ -177,4 +493,31 @@ mod test {
         };
         assert_eq!(get_called_function(&b).unwrap().to_string(), "call_foo");
     }
+
+    #[test]
+    fn test_demangle_variable_scope() {
+        let scope = |symbol| demangle_variable_scope(symbol).map(|(scope, _)| scope);
+        let internal_linkage =
+            |symbol| demangle_variable_scope(symbol).map(|(_, internal_linkage)| internal_linkage);
+        assert_eq!(
+            scope("_ZN1N6Config7versionE").unwrap(),
+            ["N", "Config", "version"]
+        );
+        assert!(!internal_linkage("_ZN1N6Config7versionE").unwrap());
+        assert_eq!(scope("_ZN1NL5limitE").unwrap(), ["N", "limit"]);
+        assert!(internal_linkage("_ZN1NL5limitE").unwrap());
+        assert_eq!(scope("_ZL7counter").unwrap(), ["counter"]);
+        assert!(internal_linkage("_ZL7counter").unwrap());
+        assert_eq!(
+            scope("__ZN6Config7versionE").unwrap(),
+            ["Config", "version"]
+        );
+        assert_eq!(
+            scope("?version@Config@N@@2HA").unwrap(),
+            ["N", "Config", "version"]
+        );
+        assert!(scope("_ZN1N5ValueIiE5countE").is_none());
+        assert!(scope("?count@?$Value@H@N@@2HA").is_none());
+        assert!(scope("Config_version").is_none());
+    }
 }
@@ -7,8 +7,8 @@
 // except according to those terms.
 
 use syn::{
-    AngleBracketedGenericArguments, GenericArgument, Path, PathArguments, PathSegment, Type,
-    TypePath, TypeReference,
+    parse_quote, AngleBracketedGenericArguments, Field, GenericArgument, Path, PathArguments,
+    PathSegment, Type, TypeBareFn, TypePath, TypeReference, TypeSlice,
 };
 
 /// Looks in a `core::pin::Pin<&mut Something>` and returns the `Something`
@@ -39,14 +39,132 @@ pub(crate) fn extract_pinned_mutable_reference_type(tp: &TypePath) -> Option<&Ty
     None
 }
 
+/// Looks in an `Option<Something>` and returns the `Something` if it's found.
+pub(crate) fn extract_option_type(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path(tp) if tp.path.segments.len() == 1 => match tp.path.segments.first() {
+            Some(PathSegment {
+                ident,
+                arguments:
+                    PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }),
+            }) if ident == "Option" && args.len() == 1 => match args.first() {
+                Some(GenericArgument::Type(inner)) => Some(inner),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// bindgen represents a C function pointer, which may be null, as a
+/// `std::option::Option<unsafe extern "C" fn(...)>`. Returns the function
+/// type if this is one of those.
+pub(crate) fn unwrap_function_pointer(ty: &TypePath) -> Option<&TypeBareFn> {
+    let segs: Vec<_> = ty.path.segments.iter().map(|seg| &seg.ident).collect();
+    if !matches!(segs.as_slice(), [std, option, last]
+        if (*std == "std" || *std == "core") && *option == "option" && *last == "Option")
+    {
+        return None;
+    }
+    match &ty.path.segments.last()?.arguments {
+        PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. })
+            if args.len() == 1 =>
+        {
+            match args.first() {
+                Some(GenericArgument::Type(Type::BareFn(bare_fn))) => Some(bare_fn),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Looks in a `&[Something]` or `&mut [Something]` and returns the `Something`
+/// if it's found.
+pub(crate) fn extract_slice_type(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Reference(TypeReference { elem, .. }) => match elem.as_ref() {
+            Type::Slice(TypeSlice { elem, .. }) => Some(elem),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Looks in a `core::mem::ManuallyDrop<Something>`, as bindgen uses for
+/// the fields of unions, and returns the `Something` if it's found.
+pub(crate) fn extract_manually_drop_type(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path(tp) if is_std_or_core_path(tp, "mem", "ManuallyDrop") => {
+            extract_single_type_argument(tp)
+        }
+        _ => None,
+    }
+}
+
+/// Looks in a `__BindgenBitfieldUnit<[u8; N]>`, which bindgen uses to
+/// store bitfields, and returns the `[u8; N]` if it's found.
+pub(crate) fn extract_bitfield_unit_storage(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path(tp)
+            if tp
+                .path
+                .segments
+                .last()
+                .map(|seg| seg.ident == "__BindgenBitfieldUnit")
+                .unwrap_or_default() =>
+        {
+            extract_single_type_argument(tp)
+        }
+        _ => None,
+    }
+}
+
+/// Marks a field which we've flattened out of an anonymous union into the
+/// type containing it.
+pub(crate) fn mark_as_anonymous_union_member(field: &mut Field) {
+    field
+        .attrs
+        .push(parse_quote! { #[autocxx_anonymous_union_member] });
+}
+
+/// Whether a field was flattened out of an anonymous union, so may not be
+/// the member of that union which is valid.
+pub(crate) fn is_anonymous_union_member(field: &Field) -> bool {
+    field
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("autocxx_anonymous_union_member"))
+}
+
+fn extract_single_type_argument(tp: &TypePath) -> Option<&Type> {
+    match &tp.path.segments.last()?.arguments {
+        PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. })
+            if args.len() == 1 =>
+        {
+            match args.first() {
+                Some(GenericArgument::Type(inner)) => Some(inner),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 /// Whether this type path is a `Pin`
 fn is_pin(tp: &TypePath) -> bool {
+    is_std_or_core_path(tp, "pin", "Pin")
+}
+
+/// Whether this type path is `std::<module>::<name>` or `core::<module>::<name>`
+fn is_std_or_core_path(tp: &TypePath, module: &str, name: &str) -> bool {
     if tp.path.segments.len() != 3 {
         return false;
     }
-    static EXPECTED_SEGMENTS: &[&[&str]] = &[&["std", "core"], &["pin"], &["Pin"]];
+    let expected_segments: &[&[&str]] = &[&["std", "core"], &[module], &[name]];
 
-    for (seg, expected_name) in tp.path.segments.iter().zip(EXPECTED_SEGMENTS.iter()) {
+    for (seg, expected_name) in tp.path.segments.iter().zip(expected_segments.iter()) {
         if !expected_name
             .iter()
             .any(|expected_name| seg.ident == expected_name)
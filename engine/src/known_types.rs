@@ -18,6 +18,7 @@ enum Behavior {
     CxxContainerPtr,
     CxxContainerVector,
     CxxString,
+    CxxByteView,
     RustStr,
     RustString,
     RustByValue,
@@ -69,6 +70,7 @@ impl TypeDetails {
             Behavior::RustString
             | Behavior::RustStr
             | Behavior::CxxString
+            | Behavior::CxxByteView
             | Behavior::CxxContainerPtr
             | Behavior::CxxContainerVector
             | Behavior::RustContainerByValueSafe => {
@@ -209,9 +211,12 @@ impl TypeDatabase {
                         | Behavior::CVariableLengthByValue
                         | Behavior::CChar16
                         | Behavior::RustContainerByValueSafe => true,
-                        Behavior::CxxString | Behavior::CxxContainerVector | Behavior::CVoid => {
-                            false
-                        }
+                        // Our representation of a view within a struct
+                        // doesn't have the right layout.
+                        Behavior::CxxString
+                        | Behavior::CxxByteView
+                        | Behavior::CxxContainerVector
+                        | Behavior::CVoid => false,
                     },
                 )
             })
@@ -238,6 +243,14 @@ impl TypeDatabase {
             .unwrap_or(false)
     }
 
+    /// Whether this is a C++ view of some bytes, which is represented
+    /// as `&[u8]` in Rust. This applies to `std::string_view`.
+    pub(crate) fn is_byte_view(&self, tn: &QualifiedName) -> bool {
+        self.get(tn)
+            .map(|td| matches!(td.behavior, Behavior::CxxByteView))
+            .unwrap_or(false)
+    }
+
     /// Whether this can only be passed around using `std::move`
     pub(crate) fn lacks_copy_constructor(&self, tn: &QualifiedName) -> bool {
         self.get(tn)
@@ -406,6 +419,17 @@ fn create_type_database() -> TypeDatabase {
         true,
         true,
     ));
+    // There's no Rust type with the layout of a `std::string_view`, so this
+    // name is only used within the prelude given to bindgen. The type
+    // converter turns views into `&[u8]` or rejects them.
+    db.insert(TypeDetails::new(
+        "CxxStringView",
+        "std::string_view",
+        Behavior::CxxByteView,
+        None,
+        true,
+        true,
+    ));
     db.insert(TypeDetails::new(
         "str",
         "rust::Str",
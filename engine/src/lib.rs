@@ -30,6 +30,7 @@ mod builder;
 use autocxx_bindgen::BindgenError;
 use autocxx_parser::{IncludeCppConfig, UnsafePolicy};
 use conversion::BridgeConverter;
+use indexmap::set::IndexSet as HashSet;
 use miette::{SourceOffset, SourceSpan};
 use parse_callbacks::{AutocxxParseCallbacks, ParseCallbackResults, UnindexedParseCallbackResults};
 use parse_file::CppBuildable;
@@ -58,6 +59,7 @@ use itertools::{join, Itertools};
 use known_types::known_types;
 use log::info;
 use miette::Diagnostic;
+use types::{bindgen_name_for_cpp_operator, CppOperator};
 
 /// We use a forked version of bindgen - for now.
 /// We hope to unfork.
@@ -348,6 +350,9 @@ impl IncludeCppEngine {
             .default_enum_style(bindgen::EnumVariation::Rust {
                 non_exhaustive: false,
             })
+            // Real Rust unions rather than structs of __BindgenUnionFields,
+            // so that POD unions can be passed by value.
+            .default_non_copy_union_style(bindgen::NonCopyUnionStyle::ManuallyDrop)
             .formatter(if log::log_enabled!(log::Level::Info) {
                 bindgen::Formatter::Rustfmt
             } else {
@@ -372,7 +377,12 @@ impl IncludeCppEngine {
         // 3. Passes allowlist and other options to the bindgen::Builder equivalent
         //    to --output-style=cxx --allowlist=<as passed in>
         if let Some(allowlist) = self.config.bindgen_allowlist() {
+            let mut namespaces = HashSet::new();
             for a in allowlist {
+                // Operators are known to bindgen by a different name (and
+                // their C++ names wouldn't be valid regexes anyway).
+                let a = bindgen_name_for_cpp_operator(&a).unwrap_or(a);
+                namespaces.insert(a.rsplit_once("::").map(|(ns, _)| format!("{ns}::")));
                 // TODO - allowlist type/functions/separately
                 builder = builder
                     .allowlist_type(&a)
@@ -380,6 +390,23 @@ impl IncludeCppEngine {
                     .allowlist_function(format!("{a}_bindgen_original"))
                     .allowlist_var(&a);
             }
+            // Streaming operators are free functions alongside the types
+            // they print, so look for them wherever we're looking for types.
+            // We filter out those for types that aren't on the allowlist
+            // later, but the standard library has far too many to bother.
+            for ns in namespaces
+                .into_iter()
+                .filter(|ns| !ns.as_ref().is_some_and(|ns| ns.starts_with("std::")))
+            {
+                let a = format!(
+                    "{}{}",
+                    ns.unwrap_or_default(),
+                    CppOperator::Shl.bindgen_name()
+                );
+                builder = builder
+                    .allowlist_function(&a)
+                    .allowlist_function(format!("{a}_bindgen_original"));
+            }
         }
 
         for item in &self.config.opaquelist {
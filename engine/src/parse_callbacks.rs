@@ -8,7 +8,7 @@
 
 use std::{cell::RefCell, fmt::Display, panic::UnwindSafe, rc::Rc};
 
-use crate::types::{strip_bindgen_original_suffix, Namespace};
+use crate::types::{strip_bindgen_original_suffix, CppOperator, Namespace};
 use crate::{conversion::CppEffectiveName, types::QualifiedName, RebuildDependencyRecorder};
 use autocxx_bindgen::callbacks::Virtualness;
 use autocxx_bindgen::callbacks::{
@@ -75,6 +75,12 @@ impl CppOriginalName {
         self.0.clone()
     }
 
+    /// If this is a C++ operator which bindgen reported under a
+    /// different name, which operator is it?
+    pub(crate) fn cpp_operator(&self) -> Option<CppOperator> {
+        CppOperator::from_bindgen_name(&self.0)
+    }
+
     /// Return the string inside for validation purposes.
     pub(crate) fn for_validation(&self) -> &str {
         &self.0
@@ -283,8 +289,15 @@ impl ParseCallbacks for AutocxxParseCallbacks {
         // namespace. But for methods, we have no way to avoid conflicts
         // if we generate an alternative implementation of a method
         // with a given name.
+        // Some C++ operators are also renamed, because bindgen can't
+        // represent names like `operator+` and would otherwise discard them.
         match _item_info.kind {
-            ItemKind::Function => Some(format!("{}_bindgen_original", _item_info.name)),
+            ItemKind::Function => {
+                let name = CppOperator::from_cpp_name(_item_info.name)
+                    .map(|op| op.bindgen_name())
+                    .unwrap_or(_item_info.name);
+                Some(format!("{name}_bindgen_original"))
+            }
             _ => None,
         }
     }
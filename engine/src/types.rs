@@ -307,9 +307,89 @@ pub(crate) fn strip_bindgen_original_suffix_from_ident(
     .0
 }
 
+/// C++ operators which we know how to turn into implementations of Rust
+/// traits. bindgen can't represent a function called `operator+` because
+/// it's not a valid identifier, so as bindgen encounters such functions we
+/// rename them (see `AutocxxParseCallbacks`) and we map them back to their
+/// C++ spelling when we generate calls to them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum CppOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    AddAssign,
+    Index,
+    Eq,
+    Ne,
+    Lt,
+    Cmp,
+    Shl,
+}
+
+/// The C++ spelling of each operator, and the name under which we ask
+/// bindgen to report it.
+const CPP_OPERATOR_NAMES: &[(CppOperator, &str, &str)] = &[
+    (CppOperator::Add, "operator+", "operator_add"),
+    (CppOperator::Sub, "operator-", "operator_sub"),
+    (CppOperator::Mul, "operator*", "operator_mul"),
+    (CppOperator::Div, "operator/", "operator_div"),
+    (CppOperator::AddAssign, "operator+=", "operator_add_assign"),
+    (CppOperator::Index, "operator[]", "operator_index"),
+    (CppOperator::Eq, "operator==", "operator_eq"),
+    (CppOperator::Ne, "operator!=", "operator_ne"),
+    (CppOperator::Lt, "operator<", "operator_lt"),
+    (CppOperator::Cmp, "operator<=>", "operator_cmp"),
+    (CppOperator::Shl, "operator<<", "operator_shl"),
+];
+
+impl CppOperator {
+    /// Identify an operator from its C++ name, e.g. `operator+`.
+    pub(crate) fn from_cpp_name(name: &str) -> Option<Self> {
+        CPP_OPERATOR_NAMES
+            .iter()
+            .find(|(_, cpp_name, _)| *cpp_name == name)
+            .map(|(op, _, _)| *op)
+    }
+
+    /// Identify an operator from the name bindgen gave it, e.g. `operator_add`.
+    pub(crate) fn from_bindgen_name(name: &str) -> Option<Self> {
+        CPP_OPERATOR_NAMES
+            .iter()
+            .find(|(_, _, bindgen_name)| *bindgen_name == name)
+            .map(|(op, _, _)| *op)
+    }
+
+    pub(crate) fn cpp_name(&self) -> &'static str {
+        self.names().1
+    }
+
+    pub(crate) fn bindgen_name(&self) -> &'static str {
+        self.names().2
+    }
+
+    fn names(&self) -> &'static (CppOperator, &'static str, &'static str) {
+        CPP_OPERATOR_NAMES
+            .iter()
+            .find(|(op, _, _)| op == self)
+            .unwrap()
+    }
+}
+
+/// Given a name like `ns::operator+`, returns the name under which bindgen
+/// will know it, e.g. `ns::operator_add`. Returns `None` for anything which
+/// isn't an operator we rename.
+pub(crate) fn bindgen_name_for_cpp_operator(name: &str) -> Option<String> {
+    let (prefix, final_item) = match name.rsplit_once("::") {
+        Some((ns, final_item)) => (format!("{ns}::"), final_item),
+        None => (String::new(), name),
+    };
+    CppOperator::from_cpp_name(final_item).map(|op| format!("{prefix}{}", op.bindgen_name()))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::types::strip_bindgen_original_suffix;
+    use crate::types::{bindgen_name_for_cpp_operator, strip_bindgen_original_suffix, CppOperator};
 
     use super::QualifiedName;
 
@@ -338,4 +418,31 @@ mod tests {
             "foo1234"
         );
     }
+
+    #[test]
+    fn test_operator_names() {
+        assert_eq!(
+            CppOperator::from_cpp_name("operator+="),
+            Some(CppOperator::AddAssign)
+        );
+        assert_eq!(
+            CppOperator::from_cpp_name("operator<=>"),
+            Some(CppOperator::Cmp)
+        );
+        assert_eq!(CppOperator::from_cpp_name("operator>"), None);
+        assert_eq!(
+            CppOperator::from_bindgen_name("operator_index"),
+            Some(CppOperator::Index)
+        );
+        assert_eq!(CppOperator::Sub.cpp_name(), "operator-");
+        assert_eq!(
+            bindgen_name_for_cpp_operator("a::b::operator*").as_deref(),
+            Some("a::b::operator_mul")
+        );
+        assert_eq!(
+            bindgen_name_for_cpp_operator("operator/").as_deref(),
+            Some("operator_div")
+        );
+        assert_eq!(bindgen_name_for_cpp_operator("a::foo"), None);
+    }
 }
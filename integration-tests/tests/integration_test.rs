@@ -2368,6 +2368,1327 @@ fn test_overload_methods() {
     run_test(cxx, hdr, rs, &["Norma"], &["Fred", "Bob"]);
 }
 
+#[test]
+fn test_default_params_function() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        inline uint32_t add(uint32_t a, uint32_t b = 2, uint32_t c = 3) {
+            return a + b + c;
+        }
+        inline uint32_t seven(uint32_t a = 7) {
+            return a;
+        }
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::add(1, 10, 100), 111);
+        assert_eq!(ffi::add_with_b(1, 10), 14);
+        assert_eq!(ffi::add_with_a(1), 6);
+        assert_eq!(ffi::seven(8), 8);
+        assert_eq!(ffi::seven_with_defaults(), 7);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("add")
+            generate!("seven")
+            default_params!("add", 2)
+            default_params!("seven", 1)
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_default_params_methods() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        namespace N {
+        class Counter {
+        public:
+            Counter(uint32_t start = 10) : count(start) {}
+            uint32_t bump(uint32_t by = 1) { count += by; return count; }
+            uint32_t get() const { return count; }
+            static uint32_t twice(uint32_t a = 21) { return a * 2; }
+        private:
+            uint32_t count;
+        };
+        }
+    "};
+    let rs = quote! {
+        use autocxx::prelude::*;
+        let mut c = ffi::N::Counter::new_with_defaults().within_unique_ptr();
+        assert_eq!(c.as_mut().unwrap().bump_with_defaults(), 11);
+        assert_eq!(c.as_mut().unwrap().bump(4), 15);
+        let c2 = ffi::N::Counter::new(3).within_unique_ptr();
+        assert_eq!(c2.get(), 3);
+        assert_eq!(ffi::N::Counter::twice_with_defaults(), 42);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("N::Counter")
+            default_params!("N::Counter::Counter", 1)
+            default_params!("N::Counter::bump", 1)
+            default_params!("N::Counter::twice", 1)
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_throws() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <stdexcept>
+        #include <string>
+        namespace N {
+        inline uint32_t parse(uint32_t a) {
+            if (a == 0) {
+                throw std::invalid_argument(\"zero\");
+            }
+            return a;
+        }
+        inline void check(bool ok) {
+            if (!ok) {
+                throw std::runtime_error(\"not ok\");
+            }
+        }
+        class Parser {
+        public:
+            std::string describe(uint32_t a) const {
+                if (a == 0) {
+                    throw std::out_of_range(\"nothing to describe\");
+                }
+                return std::string(a, 'x');
+            }
+        };
+        }
+    "};
+    let rs = quote! {
+        use autocxx::prelude::*;
+        assert_eq!(ffi::N::parse(3).unwrap(), 3);
+        assert_eq!(ffi::N::parse(0).unwrap_err().what(), "zero");
+        assert!(ffi::N::check(true).is_ok());
+        assert!(ffi::N::check(false).is_err());
+        let p = ffi::N::Parser::new().within_unique_ptr();
+        assert_eq!(p.describe(2).unwrap().to_str().unwrap(), "xx");
+        assert_eq!(p.describe(0).unwrap_err().what(), "nothing to describe");
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("N::parse")
+            generate!("N::check")
+            generate!("N::Parser")
+            throws!("N::parse")
+            throws!("N::check")
+            throws!("N::Parser::describe")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_throws_non_pod_return() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <stdexcept>
+        #include <string>
+        struct Widget {
+            std::string name;
+        };
+        inline Widget make_widget(uint32_t a) {
+            if (a == 0) {
+                throw std::invalid_argument(\"zero\");
+            }
+            Widget w;
+            w.name = \"widget\";
+            return w;
+        }
+    "};
+    let rs = quote! {
+        let w: cxx::UniquePtr<ffi::Widget> = ffi::make_widget(1).unwrap();
+        assert!(!w.is_null());
+        assert!(ffi::make_widget(0).is_err());
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("Widget")
+            generate!("make_widget")
+            throws!("make_widget")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_operator_member_pod() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <cstddef>
+        struct Vec2 {
+            int32_t x;
+            int32_t y;
+            Vec2 operator+(const Vec2& other) const { return Vec2 { x + other.x, y + other.y }; }
+            Vec2 operator-(const Vec2& other) const { return Vec2 { x - other.x, y - other.y }; }
+            Vec2 operator-() const { return Vec2 { -x, -y }; }
+            Vec2 operator*(int32_t k) const { return Vec2 { x * k, y * k }; }
+            Vec2 operator/(int32_t k) const { return Vec2 { x / k, y / k }; }
+            Vec2& operator+=(const Vec2& other) { x += other.x; y += other.y; return *this; }
+            const int32_t& operator[](size_t i) const { return i == 0 ? x : y; }
+        };
+    "};
+    let rs = quote! {
+        let a = ffi::Vec2 { x: 1, y: 2 };
+        let b = ffi::Vec2 { x: 10, y: 20 };
+        let c = &a + &b;
+        assert_eq!((c.x, c.y), (11, 22));
+        let d = &b - &a;
+        assert_eq!((d.x, d.y), (9, 18));
+        let e = -&a;
+        assert_eq!((e.x, e.y), (-1, -2));
+        let f = &a * 3;
+        assert_eq!((f.x, f.y), (3, 6));
+        let g = &b / 2;
+        assert_eq!((g.x, g.y), (5, 10));
+        assert_eq!(b[0], 10);
+        assert_eq!(b[1], 20);
+        let mut h = ffi::Vec2 { x: 0, y: 0 };
+        let mut h_pin = std::pin::Pin::new(&mut h);
+        h_pin += &a;
+        h_pin += &b;
+        assert_eq!((h.x, h.y), (11, 22));
+    };
+    run_test("", hdr, rs, &[], &["Vec2"]);
+}
+
+#[test]
+fn test_operator_free_non_pod() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        namespace N {
+        class Money {
+        public:
+            Money(int64_t cents) : cents(cents) {}
+            int64_t get_cents() const { return cents; }
+        private:
+            int64_t cents;
+            std::string currency;
+        };
+        inline Money operator+(const Money& a, const Money& b) {
+            return Money(a.get_cents() + b.get_cents());
+        }
+        inline Money operator-(const Money& a) {
+            return Money(-a.get_cents());
+        }
+        }
+    "};
+    let rs = quote! {
+        use autocxx::prelude::*;
+        let a = ffi::N::Money::new(100).within_unique_ptr();
+        let b = ffi::N::Money::new(250).within_unique_ptr();
+        let c: cxx::UniquePtr<ffi::N::Money> = a.as_ref().unwrap() + b.as_ref().unwrap();
+        assert_eq!(c.get_cents(), 350);
+        let d = -c.as_ref().unwrap();
+        assert_eq!(d.get_cents(), -350);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("N::Money")
+            generate!("N::operator+")
+            generate!("N::operator-")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_operator_free_primitive_lhs() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        struct Vec2 {
+            int32_t x;
+            int32_t y;
+        };
+        inline Vec2 operator*(int32_t k, const Vec2& v) { return Vec2 { k * v.x, k * v.y }; }
+        inline Vec2 operator*(const Vec2& v, int32_t k) { return Vec2 { v.x * k, v.y * k }; }
+    "};
+    let rs = quote! {
+        let a = ffi::Vec2 { x: 1, y: 2 };
+        let b = &a * 3;
+        assert_eq!((b.x, b.y), (3, 6));
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate_pod!("Vec2")
+            generate!("operator*")
+        },
+        None,
+        Some(make_string_finder(
+            ["first operand is a built-in type"]
+                .map(|s| s.to_string())
+                .to_vec(),
+        )),
+        None,
+    );
+}
+
+#[test]
+fn test_comparison_operators_pod() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        struct Version {
+            uint32_t major;
+            uint32_t minor;
+            bool operator==(const Version& other) const {
+                return major == other.major && minor == other.minor;
+            }
+            bool operator<(const Version& other) const {
+                return major < other.major || (major == other.major && minor < other.minor);
+            }
+        };
+    "};
+    let rs = quote! {
+        let a = ffi::Version { major: 1, minor: 2 };
+        let b = ffi::Version { major: 1, minor: 10 };
+        assert_eq!(a, ffi::Version { major: 1, minor: 2 });
+        assert!(a != b);
+        assert!(a < b);
+        assert_eq!(b.cmp(&a), std::cmp::Ordering::Greater);
+        let mut map = std::collections::BTreeMap::new();
+        map.insert(b, "b");
+        map.insert(a, "a");
+        assert_eq!(map.into_values().collect::<Vec<_>>(), vec!["a", "b"]);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate_pod!("Version")
+            total_order!("Version")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_comparison_operators_less_than_and_three_way() {
+    let hdr = indoc! {"
+        #include <compare>
+        struct Score {
+            double value;
+            std::partial_ordering operator<=>(const Score& other) const {
+                return value <=> other.value;
+            }
+            bool operator<(const Score& other) const { return value < other.value; }
+            bool operator==(const Score& other) const { return value == other.value; }
+        };
+    "};
+    let rs = quote! {
+        let a = ffi::Score { value: 1.0 };
+        let b = ffi::Score { value: 2.5 };
+        let nan = ffi::Score { value: f64::NAN };
+        assert!(a < b);
+        assert_eq!(b.partial_cmp(&a), Some(std::cmp::Ordering::Greater));
+        assert_eq!(a.partial_cmp(&nan), None);
+        assert!(a != nan);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! { generate_pod!("Score") },
+        make_clang_arg_adder(&["-std=c++20"]),
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_comparison_operators_non_pod() {
+    let hdr = indoc! {"
+        #include <compare>
+        #include <string>
+        namespace N {
+        class Name {
+        public:
+            Name(const std::string& s) : s(s) {}
+            std::strong_ordering operator<=>(const Name& other) const {
+                return s <=> other.s;
+            }
+            bool operator!=(const Name& other) const { return s != other.s; }
+        private:
+            std::string s;
+        };
+        }
+    "};
+    let rs = quote! {
+        use autocxx::prelude::*;
+        let a = ffi::N::Name::new("alice").within_box();
+        let b = ffi::N::Name::new("bob").within_box();
+        assert!(*a < *b);
+        assert!(*a != *b);
+        assert!(*a == *ffi::N::Name::new("alice").within_box());
+        assert_eq!((*a).cmp(&*b), std::cmp::Ordering::Less);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! { generate!("N::Name") },
+        make_clang_arg_adder(&["-std=c++20"]),
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_streaming_operator_display() {
+    let hdr = indoc! {"
+        #include <ostream>
+        #include <string>
+        namespace N {
+        class Widget {
+        public:
+            Widget(int id) : id(id) {}
+            int id;
+            std::string label;
+        };
+        inline std::ostream& operator<<(std::ostream& os, const Widget& w) {
+            return os << \"Widget#\" << w.id;
+        }
+        }
+    "};
+    let rs = quote! {
+        use autocxx::prelude::*;
+        let w = ffi::N::Widget::new(c_int(7)).within_box();
+        assert_eq!(format!("{}", *w), "Widget#7");
+        assert_eq!(format!("{:?}", *w), "Widget#7");
+    };
+    run_test("", hdr, rs, &["N::Widget"], &[]);
+}
+
+#[test]
+fn test_streaming_operator_only_for_allowlisted_types() {
+    let hdr = indoc! {"
+        #include <ostream>
+        namespace N {
+        struct Widget {
+            int id;
+        };
+        struct Gadget {
+            int id;
+        };
+        inline std::ostream& operator<<(std::ostream& os, const Widget& w) {
+            return os << \"Widget#\" << w.id;
+        }
+        inline std::ostream& operator<<(std::ostream& os, const Gadget& g) {
+            return os << \"Gadget#\" << g.id;
+        }
+        }
+    "};
+    let rs = quote! {
+        let w = ffi::N::Widget { id: 3 };
+        assert_eq!(format!("{}", w), "Widget#3");
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! { generate_pod!("N::Widget") },
+        None,
+        Some(Box::new(CppMatcher::new(&["Widget"], &["Gadget"]))),
+        None,
+    );
+}
+
+#[test]
+fn test_std_hash() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <functional>
+        namespace N {
+        struct Point {
+            uint32_t x;
+            uint32_t y;
+            bool operator==(const Point& other) const { return x == other.x && y == other.y; }
+        };
+        }
+        template <> struct std::hash<N::Point> {
+            size_t operator()(const N::Point& p) const { return p.x * 31 + p.y; }
+        };
+    "};
+    let rs = quote! {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        ffi::N::Point { x: 1, y: 2 }.hash(&mut hasher);
+        let mut expected = std::collections::hash_map::DefaultHasher::new();
+        expected.write_usize(33);
+        assert_eq!(hasher.finish(), expected.finish());
+        assert!(ffi::N::Point { x: 1, y: 2 } == ffi::N::Point { x: 1, y: 2 });
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate_pod!("N::Point")
+            hashable!("N::Point")
+        },
+        make_cpp17_adder(),
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_iterate_pointer_range() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <vector>
+        namespace N {
+        struct Item {
+            uint32_t a;
+        };
+        class Bag {
+        public:
+            Bag() : items{{1}, {2}, {3}} {}
+            const Item* begin() const { return items.data(); }
+            const Item* end() const { return items.data() + items.size(); }
+            Item* begin() { return items.data(); }
+            Item* end() { return items.data() + items.size(); }
+        private:
+            std::vector<Item> items;
+        };
+        }
+    "};
+    let rs = quote! {
+        let mut bag = ffi::N::Bag::new().within_unique_ptr();
+        for item in bag.pin_mut() {
+            item.get_mut().a *= 10;
+        }
+        let bag = bag.as_ref().unwrap();
+        assert_eq!(bag.into_iter().map(|item| item.a).collect::<Vec<_>>(), vec![10, 20, 30]);
+    };
+    run_test("", hdr, rs, &["N::Bag"], &["N::Item"]);
+}
+
+#[test]
+fn test_iterate_value_type_range() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <vector>
+        namespace N {
+        struct Item {
+            uint32_t a;
+        };
+        class Bag {
+        public:
+            typedef Item value_type;
+            Bag() : items{{1}, {2}, {3}} {}
+            std::vector<Item>::const_iterator begin() const { return items.begin(); }
+            std::vector<Item>::const_iterator end() const { return items.end(); }
+        private:
+            std::vector<Item> items;
+        };
+        }
+    "};
+    let rs = quote! {
+        let bag = ffi::N::Bag::new().within_unique_ptr();
+        let mut total = 0;
+        for item in bag.as_ref().unwrap() {
+            total += item.a;
+        }
+        assert_eq!(total, 6);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("N::Bag")
+            generate_pod!("N::Item")
+            iterable!("N::Bag")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_string_view() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        #include <string_view>
+        inline uint32_t count_bytes(std::string_view s) { return s.size(); }
+        class Person {
+        public:
+            Person() : name(\"Bob\") {}
+            std::string_view get_name() const { return name; }
+            bool is_called(const std::string_view& other) const { return name == other; }
+        private:
+            std::string name;
+        };
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::count_bytes("hello"), 5);
+        assert_eq!(ffi::count_bytes(b"hi"), 2);
+        let person = ffi::Person::new().within_unique_ptr();
+        assert_eq!(person.get_name(), b"Bob");
+        assert!(person.is_called("Bob"));
+        assert!(!person.is_called(&String::from("Anna")));
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        directives_from_lists(&["count_bytes", "Person"], &[], None),
+        make_cpp17_adder(),
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_span_of_chars() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <span>
+        inline uint32_t count_as(std::span<const char> s) {
+            uint32_t count = 0;
+            for (auto c : s) {
+                if (c == 'a') {
+                    count++;
+                }
+            }
+            return count;
+        }
+    "};
+    let rs = quote! {
+        let bytes: &[u8] = b"banana";
+        assert_eq!(ffi::count_as(bytes), 3);
+        assert_eq!(ffi::count_as("aardvark"), 3);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        directives_from_lists(&["count_as"], &[], None),
+        make_clang_arg_adder(&["-std=c++20"]),
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_string_view_in_container() {
+    // A vector of views can't be represented, but mustn't stop us
+    // generating anything else.
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string_view>
+        #include <vector>
+        inline uint32_t count_views(const std::vector<std::string_view>& views) {
+            return views.size();
+        }
+        inline uint32_t count_bytes(std::string_view s) { return s.size(); }
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::count_bytes("hello"), 5);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        directives_from_lists(&["count_views", "count_bytes"], &[], None),
+        make_cpp17_adder(),
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_optional() {
+    let hdr = indoc! {"
+        #include <cstddef>
+        #include <cstdint>
+        #include <optional>
+        #include <string>
+        namespace N {
+        struct Point {
+            uint32_t x;
+            uint32_t y;
+        };
+        }
+        inline std::optional<uint32_t> maybe_double(std::optional<uint32_t> a) {
+            if (a) {
+                return *a * 2;
+            }
+            return std::nullopt;
+        }
+        inline std::optional<N::Point> maybe_point(bool b) {
+            if (b) {
+                return N::Point { 1, 2 };
+            }
+            return std::nullopt;
+        }
+        inline std::optional<std::string> maybe_string(bool b) {
+            if (b) {
+                return \"hello\";
+            }
+            return std::nullopt;
+        }
+        inline size_t string_len(std::optional<std::string> s) {
+            return s ? s->size() : 0;
+        }
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::maybe_double(Some(4)), Some(8));
+        assert_eq!(ffi::maybe_double(None), None);
+        assert_eq!(ffi::maybe_point(true).unwrap().y, 2);
+        assert!(ffi::maybe_point(false).is_none());
+        assert_eq!(ffi::maybe_string(true).unwrap().to_str().unwrap(), "hello");
+        assert!(ffi::maybe_string(false).is_none());
+        assert_eq!(ffi::string_len(Some(ffi::make_string("abc"))), 3);
+        assert_eq!(ffi::string_len(None), 0);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("maybe_double")
+            generate!("maybe_point")
+            generate!("maybe_string")
+            generate!("string_len")
+            generate_pod!("N::Point")
+        },
+        make_cpp17_adder(),
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_span() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <span>
+        namespace N {
+        struct Point {
+            uint32_t x;
+            uint32_t y;
+        };
+        }
+        inline uint32_t sum(std::span<const uint32_t> values) {
+            uint32_t total = 0;
+            for (auto v : values) {
+                total += v;
+            }
+            return total;
+        }
+        inline void double_all(std::span<N::Point> points) {
+            for (auto& p : points) {
+                p.x *= 2;
+                p.y *= 2;
+            }
+        }
+        inline void fill(std::span<char> chars) {
+            for (auto& c : chars) {
+                c = 'x';
+            }
+        }
+    "};
+    let rs = quote! {
+        let values = [1u32, 2, 3];
+        assert_eq!(ffi::sum(&values), 6);
+        let mut points = vec![ffi::N::Point { x: 1, y: 2 }, ffi::N::Point { x: 3, y: 4 }];
+        ffi::double_all(&mut points);
+        assert_eq!(points[1].y, 8);
+        let mut chars = [0 as std::os::raw::c_char; 2];
+        ffi::fill(&mut chars);
+        assert_eq!(chars[1], b'x' as std::os::raw::c_char);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("sum")
+            generate!("double_all")
+            generate!("fill")
+            generate_pod!("N::Point")
+            mutable_span!("double_all", "points")
+            mutable_span!("fill", "chars")
+        },
+        make_clang_arg_adder(&["-std=c++20"]),
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_span_of_fixed_extent() {
+    // We can't make a span of fixed extent from a slice, but that mustn't
+    // stop us generating anything else.
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <span>
+        inline uint32_t sum_pair(std::span<const uint32_t, 2> values) {
+            return values[0] + values[1];
+        }
+        inline uint32_t sum(std::span<const uint32_t> values) {
+            uint32_t total = 0;
+            for (auto v : values) {
+                total += v;
+            }
+            return total;
+        }
+    "};
+    let rs = quote! {
+        let values = [1u32, 2, 3];
+        assert_eq!(ffi::sum(&values), 6);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        directives_from_lists(&["sum_pair", "sum"], &[], None),
+        make_clang_arg_adder(&["-std=c++20"]),
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_map() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <map>
+        #include <string>
+        #include <unordered_map>
+        class Scores {
+        public:
+            Scores() : ages{{\"Bob\", 42}} {}
+            std::map<uint32_t, uint32_t>& get_squares() { return squares; }
+            const std::unordered_map<std::string, uint32_t>& get_ages() const { return ages; }
+        private:
+            std::map<uint32_t, uint32_t> squares;
+            std::unordered_map<std::string, uint32_t> ages;
+        };
+    "};
+    let rs = quote! {
+        let mut scores = ffi::Scores::new().within_unique_ptr();
+        let mut squares = scores.pin_mut().get_squares();
+        assert!(squares.as_mut().insert(2, 4));
+        assert!(squares.as_mut().insert(3, 10));
+        assert!(!squares.as_mut().insert(3, 9));
+        assert_eq!(squares.len(), 2);
+        assert_eq!(squares.get(&3), Some(&9));
+        assert_eq!(squares.get(&4), None);
+        assert!(squares.contains_key(&2));
+        let keys: Vec<u32> = (&*squares).into_iter().map(|entry| *entry.key()).collect();
+        assert_eq!(keys, vec![2, 3]);
+        let total: u32 = (&*squares).into_iter().map(|entry| *entry.value()).sum();
+        assert_eq!(total, 13);
+        assert!(squares.as_mut().remove(&2));
+        assert!(!squares.as_mut().remove(&2));
+        assert_eq!(squares.len(), 1);
+        let ages = scores.get_ages();
+        assert_eq!(ages.get(&ffi::make_string("Bob")), Some(&42));
+        assert!(!ages.contains_key(&ffi::make_string("Anna")));
+    };
+    run_test("", hdr, rs, &["Scores"], &[]);
+}
+
+#[test]
+fn test_pair_and_tuple() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <tuple>
+        #include <utility>
+        namespace N {
+        struct Point {
+            uint32_t x;
+            uint32_t y;
+        };
+        }
+        inline std::pair<uint32_t, bool> checked_half(uint32_t a) {
+            return std::make_pair(a / 2, a % 2 == 0);
+        }
+        inline uint32_t sum_pair(std::pair<uint32_t, uint32_t> p) {
+            return p.first + p.second;
+        }
+        inline std::tuple<N::Point, uint8_t, bool> point_and_more(uint32_t x) {
+            return std::make_tuple(N::Point { x, x + 1 }, 3, true);
+        }
+        inline uint32_t sum_tuple(std::tuple<uint32_t, N::Point> t) {
+            return std::get<0>(t) + std::get<1>(t).x + std::get<1>(t).y;
+        }
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::checked_half(8), (4, true));
+        assert_eq!(ffi::checked_half(7), (3, false));
+        assert_eq!(ffi::sum_pair((2, 3)), 5);
+        let (pt, a, b) = ffi::point_and_more(4);
+        assert_eq!(pt.y, 5);
+        assert_eq!(a, 3);
+        assert!(b);
+        assert_eq!(ffi::sum_tuple((1, ffi::N::Point { x: 2, y: 3 })), 6);
+    };
+    run_test(
+        "",
+        hdr,
+        rs,
+        &["checked_half", "sum_pair", "point_and_more", "sum_tuple"],
+        &["N::Point"],
+    );
+}
+
+#[test]
+fn test_variant() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        #include <variant>
+        namespace N {
+        struct Point {
+            uint32_t x;
+            uint32_t y;
+        };
+        }
+        inline std::variant<uint32_t, N::Point, std::string> make_shape(uint32_t which) {
+            switch (which) {
+            case 0:
+                return uint32_t(7);
+            case 1:
+                return N::Point { 1, 2 };
+            default:
+                return std::string(\"hello\");
+            }
+        }
+        inline uint32_t describe(std::variant<uint32_t, N::Point, std::string> v) {
+            if (auto* p = std::get_if<N::Point>(&v)) {
+                return p->x + p->y;
+            }
+            if (auto* s = std::get_if<std::string>(&v)) {
+                return static_cast<uint32_t>(s->size());
+            }
+            return std::get<uint32_t>(v);
+        }
+    "};
+    let rs = quote! {
+        use ffi::std_variant_uint32_t_N_Point_std_string_AutocxxVariant as Shape;
+        assert!(matches!(ffi::make_shape(0), Shape::U32(7)));
+        assert!(matches!(ffi::make_shape(1), Shape::Point(ffi::N::Point { x: 1, y: 2 })));
+        match ffi::make_shape(2) {
+            Shape::CxxString(s) => assert_eq!(s.to_str().unwrap(), "hello"),
+            _ => panic!("wrong alternative"),
+        }
+        assert_eq!(ffi::describe(Shape::U32(3)), 3);
+        assert_eq!(ffi::describe(Shape::Point(ffi::N::Point { x: 1, y: 2 })), 3);
+        assert_eq!(ffi::describe(Shape::CxxString(ffi::make_string("abcd"))), 4);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("make_shape")
+            generate!("describe")
+            generate_pod!("N::Point")
+        },
+        make_cpp17_adder(),
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_c_array_reference() {
+    let hdr = indoc! {"
+    #include <cstdint>
+    inline uint32_t sum(const uint32_t (&a)[4]) {
+        return a[0] + a[1] + a[2] + a[3];
+    }
+    inline void fill(uint32_t (&a)[3], uint32_t value) {
+        for (auto& v : a) {
+            v = value;
+        }
+    }
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::sum(&[1, 2, 3, 4]), 10);
+        let mut a = [0u32; 3];
+        ffi::fill(&mut a, 7);
+        assert_eq!(a, [7, 7, 7]);
+    };
+    run_test("", hdr, rs, &["sum", "fill"], &[]);
+}
+
+#[test]
+fn test_std_array() {
+    let hdr = indoc! {"
+    #include <array>
+    #include <cstdint>
+    inline std::array<uint32_t, 4> make(uint32_t value) {
+        return {value, value + 1, value + 2, value + 3};
+    }
+    inline uint32_t sum(const std::array<uint32_t, 4>& a) {
+        return a[0] + a[1] + a[2] + a[3];
+    }
+    inline uint32_t first(std::array<uint32_t, 2> a) {
+        return a[0];
+    }
+    inline void fill(std::array<uint32_t, 3>& a, uint32_t value) {
+        a.fill(value);
+    }
+    "};
+    let rs = quote! {
+        let a = ffi::make(1);
+        assert_eq!(a, [1, 2, 3, 4]);
+        assert_eq!(ffi::sum(&a), 10);
+        assert_eq!(ffi::first([5, 6]), 5);
+        let mut b = [0u32; 3];
+        ffi::fill(&mut b, 7);
+        assert_eq!(b, [7, 7, 7]);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("make")
+            generate!("sum")
+            generate!("first")
+            generate!("fill")
+            std_array!("make", "return", [u32; 4])
+            std_array!("sum", "a", [u32; 4])
+            std_array!("first", "a", [u32; 2])
+            std_array!("fill", "a", [u32; 3])
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_pod_with_array_of_pod_field() {
+    let hdr = indoc! {"
+    #include <cstdint>
+    struct Point {
+        uint32_t x;
+        uint32_t y;
+    };
+    struct Triangle {
+        Point corners[3];
+    };
+    inline uint32_t sum_x(Triangle t) {
+        return t.corners[0].x + t.corners[1].x + t.corners[2].x;
+    }
+    "};
+    let rs = quote! {
+        let p = |x| ffi::Point { x, y: 0 };
+        let t = ffi::Triangle { corners: [p(1), p(2), p(3)] };
+        assert_eq!(ffi::sum_x(t), 6);
+    };
+    run_test("", hdr, rs, &["sum_x"], &["Triangle"]);
+}
+
+#[test]
+fn test_pod_with_array_of_non_pod_field() {
+    let hdr = indoc! {"
+    #include <string>
+    struct Names {
+        std::string names[2];
+    };
+    "};
+    run_test_expect_fail("", hdr, quote! {}, &[], &["Names"]);
+}
+
+#[test]
+fn test_field_accessors() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        namespace N {
+        class Person {
+        public:
+            Person() : name(\"Alice\"), age(30), secret(4) {}
+            std::string name;
+            uint32_t age;
+            uint32_t get_secret() const { return secret; }
+        private:
+            uint32_t secret;
+        };
+        }
+    "};
+    let rs = quote! {
+        let mut p = ffi::N::Person::new().within_unique_ptr();
+        assert_eq!(p.name().to_str().unwrap(), "Alice");
+        assert_eq!(*p.age(), 30);
+        p.pin_mut().name_mut().push_str("!");
+        *p.pin_mut().age_mut() = 31;
+        assert_eq!(p.name().to_str().unwrap(), "Alice!");
+        assert_eq!(*p.age(), 31);
+        assert_eq!(p.get_secret(), 4);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("N::Person")
+            field_accessors!("N::Person")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_field_accessors_skip_keyword_fields() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        struct Token {
+            Token() : type(\"word\"), length(4) {}
+            std::string type;
+            uint32_t length;
+        };
+    "};
+    let rs = quote! {
+        let t = ffi::Token::new().within_unique_ptr();
+        assert_eq!(*t.length(), 4);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("Token")
+            field_accessors!("Token")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_field_accessors_with_anonymous_union() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        struct Value {
+            Value() : name(\"int\"), i(3) {}
+            std::string name;
+            union {
+                uint32_t i;
+                float f;
+            };
+        };
+    "};
+    let rs = quote! {
+        let v = ffi::Value::new().within_unique_ptr();
+        assert_eq!(v.name().to_str().unwrap(), "int");
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("Value")
+            field_accessors!("Value")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_field_accessors_skip_anonymous_union_members() {
+    // Only one member of the union is valid at a time, so we can't give
+    // out references to any of them.
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        struct Value {
+            Value() : name(\"int\"), i(3) {}
+            std::string name;
+            union {
+                uint32_t i;
+                float f;
+            };
+        };
+    "};
+    let rs = quote! {
+        let v = ffi::Value::new().within_unique_ptr();
+        v.i();
+    };
+    run_test_expect_fail_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("Value")
+            field_accessors!("Value")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_field_accessors_not_requested() {
+    let hdr = indoc! {"
+        #include <string>
+        struct Person {
+            std::string name;
+        };
+    "};
+    let rs = quote! {
+        let p = ffi::Person::new().within_unique_ptr();
+        p.name();
+    };
+    run_test_expect_fail("", hdr, rs, &["Person"], &[]);
+}
+
+#[test]
+fn test_global_variables() {
+    let cxx = indoc! {"
+        uint32_t counter = 3;
+        namespace N {
+        const uint32_t limit = 10;
+        }
+    "};
+    let hdr = indoc! {"
+        #include <cstdint>
+        extern uint32_t counter;
+        namespace N {
+        extern const uint32_t limit;
+        }
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::get_counter(), 3);
+        *unsafe { ffi::counter_mut() } = 4;
+        assert_eq!(ffi::get_counter(), 4);
+        assert_eq!(*ffi::counter_ref(), 4);
+        assert_eq!(ffi::N::get_limit(), 10);
+        assert_eq!(*ffi::N::limit_ref(), 10);
+    };
+    run_test(cxx, hdr, rs, &["counter", "N::limit"], &[]);
+}
+
+#[test]
+fn test_const_global_variable_not_mutable() {
+    let cxx = indoc! {"
+        const uint32_t limit = 10;
+    "};
+    let hdr = indoc! {"
+        #include <cstdint>
+        extern const uint32_t limit;
+    "};
+    let rs = quote! {
+        unsafe { ffi::limit_mut() };
+    };
+    run_test_expect_fail(cxx, hdr, rs, &["limit"], &[]);
+}
+
+#[test]
+fn test_mutable_global_variable_access_is_unsafe() {
+    let cxx = indoc! {"
+        uint32_t counter = 3;
+    "};
+    let hdr = indoc! {"
+        #include <cstdint>
+        extern uint32_t counter;
+    "};
+    let rs = quote! {
+        *ffi::counter_mut() = 4;
+    };
+    run_test_expect_fail(cxx, hdr, rs, &["counter"], &[]);
+}
+
+#[test]
+fn test_static_data_members() {
+    let cxx = indoc! {"
+        uint32_t Registry::count = 1;
+        const std::string Registry::name = \"reg\";
+    "};
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        class Registry {
+        public:
+            static uint32_t count;
+            static const std::string name;
+        };
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::Registry::get_count(), 1);
+        *unsafe { ffi::Registry::count_mut() } = 2;
+        assert_eq!(*ffi::Registry::count_ref(), 2);
+        assert_eq!(ffi::Registry::name_ref().to_str().unwrap(), "reg");
+    };
+    run_test(cxx, hdr, rs, &["Registry"], &[]);
+}
+
+#[test]
+fn test_global_variable_named_like_static_data_member() {
+    let cxx = indoc! {"
+        uint32_t Config_version = 1;
+        uint32_t Config::version = 2;
+    "};
+    let hdr = indoc! {"
+        #include <cstdint>
+        extern uint32_t Config_version;
+        struct Config {
+            static uint32_t version;
+        };
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::get_Config_version(), 1);
+        assert_eq!(ffi::Config::get_version(), 2);
+    };
+    run_test(cxx, hdr, rs, &["Config_version", "Config"], &[]);
+}
+
 #[test]
 fn test_ns_constructor() {
     let cxx = indoc! {"
@@ -5083,11 +6404,171 @@ fn test_union_pod() {
         uint32_t a;
         float b;
     };
+    inline uint32_t get_a(A a) {
+        return a.a;
+    }
+    "};
+    let rs = quote! {
+        let a = ffi::A { a: ::std::mem::ManuallyDrop::new(3) };
+        assert_eq!(ffi::get_a(a), 3);
+        let a = ffi::A { b: ::std::mem::ManuallyDrop::new(1.0) };
+        assert_eq!(unsafe { *a.b }, 1.0);
+    };
+    run_test("", hdr, rs, &["get_a"], &["A"]);
+}
+
+#[test]
+fn test_union_pod_with_non_pod_member() {
+    let hdr = indoc! {"
+    #include <cstdint>
+    #include <string>
+    union A {
+        A() : a(0) {}
+        ~A() {}
+        uint32_t a;
+        std::string b;
+    };
     "};
     let rs = quote! {};
     run_test_expect_fail("", hdr, rs, &[], &["A"]);
 }
 
+#[test]
+fn test_anonymous_union_in_pod() {
+    let hdr = indoc! {"
+    #include <cstdint>
+    struct Value {
+        uint32_t tag;
+        union {
+            uint32_t i;
+            float f;
+        };
+    };
+    inline Value make_int(uint32_t i) {
+        Value v;
+        v.tag = 0;
+        v.i = i;
+        return v;
+    }
+    inline float get_float(Value v) {
+        return v.f;
+    }
+    "};
+    let rs = quote! {
+        let v = ffi::make_int(7);
+        assert_eq!(v.tag, 0);
+        assert_eq!(unsafe { *v.__bindgen_anon_1.i }, 7);
+        let mut v = v;
+        v.tag = 1;
+        v.__bindgen_anon_1.f = ::std::mem::ManuallyDrop::new(2.0);
+        assert_eq!(ffi::get_float(v), 2.0);
+    };
+    run_test("", hdr, rs, &["make_int", "get_float"], &["Value"]);
+}
+
+#[test]
+fn test_bitfields_in_pod() {
+    let hdr = indoc! {"
+    #include <cstdint>
+    struct Flags {
+        uint32_t a : 3;
+        uint32_t b : 5;
+        uint16_t other;
+    };
+    inline Flags make_flags() {
+        Flags f;
+        f.a = 5;
+        f.b = 17;
+        f.other = 2;
+        return f;
+    }
+    inline uint32_t get_b(Flags f) {
+        return f.b;
+    }
+    "};
+    let rs = quote! {
+        let mut f = ffi::make_flags();
+        assert_eq!(f.get_a(), 5);
+        assert_eq!(f.get_b(), 17);
+        assert_eq!(f.other, 2);
+        f.set_b(9);
+        assert_eq!(f.get_a(), 5);
+        assert_eq!(ffi::get_b(f), 9);
+    };
+    run_test("", hdr, rs, &["make_flags", "get_b"], &["Flags"]);
+}
+
+#[test]
+fn test_bitfield_getter_named_like_method() {
+    let hdr = indoc! {"
+    #include <cstdint>
+    struct Flags {
+        uint32_t a : 3;
+        uint32_t b : 5;
+        uint32_t get_a() const { return a + 1; }
+    };
+    inline Flags make_flags() {
+        Flags f;
+        f.a = 5;
+        f.b = 17;
+        return f;
+    }
+    "};
+    let rs = quote! {
+        let f = ffi::make_flags();
+        assert_eq!(f.get_a(), 6);
+        assert_eq!(f.a(), 5);
+        assert_eq!(f.get_b(), 17);
+    };
+    run_test("", hdr, rs, &["make_flags"], &["Flags"]);
+}
+
+#[test]
+fn test_bitfield_setter_named_like_method() {
+    let hdr = indoc! {"
+    #include <cstdint>
+    struct Flags {
+        uint32_t a : 3;
+        uint32_t b : 5;
+        void set_a(uint32_t value) { a = value - 1; }
+        uint32_t get_a() const { return a; }
+    };
+    inline Flags make_flags() {
+        Flags f;
+        f.a = 5;
+        f.b = 17;
+        return f;
+    }
+    "};
+    let rs = quote! {
+        let mut f = ffi::make_flags();
+        std::pin::Pin::new(&mut f).set_a(3);
+        assert_eq!(f.get_a(), 2);
+        f.set_b(9);
+        assert_eq!(f.get_b(), 9);
+    };
+    run_test("", hdr, rs, &["make_flags"], &["Flags"]);
+}
+
+#[test]
+fn test_private_bitfields_in_pod() {
+    let hdr = indoc! {"
+    #include <cstdint>
+    class Flags {
+    public:
+        Flags() : a(1) {}
+        uint32_t get_a() const { return a; }
+    private:
+        uint32_t a : 3;
+    };
+    "};
+    let rs = quote! {
+        let f = ffi::Flags::new().within_box();
+        assert_eq!(f.get_a(), 1);
+    };
+    run_test("", hdr, rs, &[], &["Flags"]);
+}
+
 #[test]
 fn test_type_aliased_anonymous_union_ignored() {
     let hdr = indoc! {"
@@ -5540,8 +7021,100 @@ fn test_pointer_to_pointer() {
         class a {};
         } // namespace operations_research
     "};
-    let rs = quote! {};
-    run_test("", hdr, rs, &["operations_research::Solver"], &[]);
+    let rs = quote! {};
+    run_test("", hdr, rs, &["operations_research::Solver"], &[]);
+}
+
+#[test]
+fn test_pointer_to_pointer_param() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        inline uint32_t deref_twice(uint32_t** p) { return **p; }
+    "};
+    let rs = quote! {
+        let mut v = 4u32;
+        let mut p: *mut u32 = &mut v;
+        assert_eq!(unsafe { ffi::deref_twice(&mut p) }, 4);
+    };
+    run_test("", hdr, rs, &["deref_twice"], &[]);
+}
+
+#[test]
+fn test_out_params() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        class Foo {
+        public:
+            Foo(uint32_t a) : a(a) {}
+            uint32_t get() const { return a; }
+        private:
+            uint32_t a;
+        };
+        inline bool create_foo(uint32_t a, Foo** out) {
+            if (a == 0) {
+                return false;
+            }
+            *out = new Foo(a);
+            return true;
+        }
+        class Factory {
+        public:
+            static void make(Foo** first, Foo** second) {
+                *first = new Foo(1);
+                *second = new Foo(2);
+            }
+        };
+    "};
+    let rs = quote! {
+        let (created, foo) = ffi::create_foo(3);
+        assert!(created);
+        assert_eq!(foo.get(), 3);
+        let (created, foo) = ffi::create_foo(0);
+        assert!(!created);
+        assert!(foo.is_null());
+        let (first, second) = ffi::Factory::make();
+        assert_eq!(first.get(), 1);
+        assert_eq!(second.get(), 2);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("Foo")
+            generate!("create_foo")
+            generate!("Factory")
+            out_param!("create_foo", "out")
+            out_param!("Factory::make", "first")
+            out_param!("Factory::make", "second")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_out_param_not_pointer_to_pointer() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        inline void get_count(uint32_t* out) { *out = 3; }
+    "};
+    let rs = quote! {
+        ffi::get_count();
+    };
+    run_test_expect_fail_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("get_count")
+            out_param!("get_count", "out")
+        },
+        None,
+        None,
+        None,
+    );
 }
 
 #[test]
@@ -5844,6 +7417,57 @@ fn test_struct_with_rvalue() {
     run_test("", hdr, rs, &["A", "B"], &[]);
 }
 
+#[test]
+fn test_functions_using_struct_with_rvalue() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <memory>
+        #include <utility>
+        struct A {
+            uint32_t a;
+        };
+        struct B {
+            B(A&& param) : a(std::move(param)) {}
+            A&& a;
+        };
+        inline std::unique_ptr<B> make_b(A& a) {
+            return std::make_unique<B>(std::move(a));
+        }
+        inline uint32_t get_via_ref(const B& b) { return b.a.a; }
+        inline uint32_t get_via_ptr(const B* b) { return b->a.a; }
+    "};
+    let rs = quote! {
+        let mut a = ffi::A { a: 12 };
+        let b = ffi::make_b(&mut a);
+        assert_eq!(ffi::get_via_ref(&b), 12);
+        assert_eq!(unsafe { ffi::get_via_ptr(b.as_ref().unwrap()) }, 12);
+    };
+    run_test(
+        "",
+        hdr,
+        rs,
+        &["B", "make_b", "get_via_ref", "get_via_ptr"],
+        &["A"],
+    );
+}
+
+#[test]
+fn test_pod_struct_with_rvalue() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <utility>
+        struct A {
+            uint32_t a;
+        };
+        struct B {
+            B(A&& param) : a(std::move(param)) {}
+            A&& a;
+        };
+    "};
+    let rs = quote! {};
+    run_test_expect_fail("", hdr, rs, &[], &["A", "B"]);
+}
+
 #[test]
 fn test_immovable_nested_object() {
     let hdr = indoc! {"
@@ -5970,6 +7594,8 @@ fn test_private_inheritance() {
 
 #[test]
 fn test_error_generated_for_static_data() {
+    // FOO has internal linkage, so the copy our C++ accesses isn't the one
+    // seen by any other translation unit.
     let hdr = indoc! {"
         #include <cstdint>
         struct A {
@@ -5990,6 +7616,22 @@ fn test_error_generated_for_static_data() {
     );
 }
 
+#[test]
+fn test_static_const_data() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        struct A {
+            A() : a(3) {}
+            uint32_t a;
+        };
+        static const A FOO = A();
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::FOO_ref().a, 3);
+    };
+    run_test("", hdr, rs, &["FOO"], &["A"]);
+}
+
 #[test]
 #[cfg_attr(skip_windows_gnu_failing_tests, ignore)]
 #[cfg_attr(skip_windows_msvc_failing_tests, ignore)]
@@ -6556,6 +8198,55 @@ fn test_take_nonpod_rvalue_from_stack() {
     run_test("", hdr, rs, &["A", "take_a"], &[]);
 }
 
+#[test]
+fn test_return_nonpod_rvalue() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        #include <utility>
+        struct Buffer {
+            std::string contents;
+            uint32_t len() const { return contents.size(); }
+        };
+        class Holder {
+        public:
+            Holder() { buf.contents = \"hello\"; }
+            Buffer&& take() { return std::move(buf); }
+            uint32_t remaining() const { return buf.contents.size(); }
+        private:
+            Buffer buf;
+        };
+    "};
+    let rs = quote! {
+        let mut holder = ffi::Holder::new().within_unique_ptr();
+        moveit! { let buf = holder.pin_mut().take() };
+        assert_eq!(buf.len(), 5);
+        assert_eq!(holder.remaining(), 0);
+        let mut holder2 = ffi::Holder::new().within_unique_ptr();
+        let buf2 = holder2.pin_mut().take().within_unique_ptr();
+        assert_eq!(buf2.len(), 5);
+    };
+    run_test("", hdr, rs, &["Buffer", "Holder"], &[]);
+}
+
+#[test]
+fn test_return_pod_rvalue() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <utility>
+        struct A {
+            uint32_t a;
+        };
+        inline A&& pass_through(A& a) { return std::move(a); }
+    "};
+    let rs = quote! {
+        let mut a = ffi::A { a: 12 };
+        let b = ffi::pass_through(&mut a);
+        assert_eq!(b.a, 12);
+    };
+    run_test("", hdr, rs, &["pass_through"], &["A"]);
+}
+
 #[test]
 fn test_overloaded_ignored_function() {
     // When overloaded functions are ignored during import, the placeholder
@@ -11525,6 +13216,45 @@ fn test_concretize() {
     );
 }
 
+#[test]
+fn test_concrete_type_methods() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        template<typename CONTENTS>
+        class Container {
+        public:
+            Container(CONTENTS contents) : contents(contents) {}
+            CONTENTS get() const { return contents; }
+            void set(CONTENTS new_contents) { contents = new_contents; }
+        private:
+            CONTENTS contents;
+        };
+        inline Container<uint32_t> make_container(uint32_t contents) {
+            return Container<uint32_t>(contents);
+        }
+    "};
+    let rs = quote! {
+        let mut container: cxx::UniquePtr<ffi::ContainerOfU32> = ffi::make_container(3);
+        assert_eq!(container.get(), 3);
+        container.pin_mut().set(4);
+        assert_eq!(container.get(), 4);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("make_container")
+            concrete!("Container<uint32_t>", ContainerOfU32)
+            concrete_method!(ContainerOfU32, "get", (&self) -> u32)
+            concrete_method!(ContainerOfU32, "set", (&mut self, u32))
+        },
+        None,
+        None,
+        None,
+    );
+}
+
 #[test]
 fn test_doc_comments_survive() {
     let hdr = indoc! {"
@@ -11613,6 +13343,265 @@ fn test_variadic() {
     run_test("", hdr, quote! {}, &["SomeClass"], &[]);
 }
 
+#[test]
+fn test_variadic_instantiations() {
+    let hdr = indoc! {"
+        #include <cstdarg>
+        #include <cstdint>
+        inline int32_t sum_ints(int32_t count, ...) {
+            va_list args;
+            va_start(args, count);
+            int32_t total = 0;
+            for (int32_t i = 0; i < count; i++) {
+                total += va_arg(args, int32_t);
+            }
+            va_end(args);
+            return total;
+        }
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::sum_ints(1, 5), 5);
+        assert_eq!(ffi::sum_ints1(3, 1, 2, 3), 6);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("sum_ints")
+            variadic!("sum_ints", (i32))
+            variadic!("sum_ints", (i32, i32, i32))
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_variadic_printf() {
+    let hdr = indoc! {"
+        #include <cstdarg>
+        #include <cstdio>
+        namespace logging {
+        inline int format_into(char* buf, size_t len, const char* fmt, ...) {
+            va_list args;
+            va_start(args, fmt);
+            int written = vsnprintf(buf, len, fmt, args);
+            va_end(args);
+            return written;
+        }
+        }
+    "};
+    let rs = quote! {
+        let fmt = std::ffi::CString::new("%d %s").unwrap();
+        let name = std::ffi::CString::new("bob").unwrap();
+        let mut buf = [0 as std::os::raw::c_char; 32];
+        let written = unsafe {
+            ffi::logging::format_into(
+                buf.as_mut_ptr(),
+                buf.len(),
+                fmt.as_ptr(),
+                autocxx::c_int(42),
+                name.as_ptr(),
+            )
+        };
+        assert_eq!(written, autocxx::c_int(6));
+        let formatted = unsafe { std::ffi::CStr::from_ptr(buf.as_ptr()) };
+        assert_eq!(formatted.to_str().unwrap(), "42 bob");
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("logging::format_into")
+            variadic!("logging::format_into", (c_int, *const c_char))
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_std_function_closure() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <functional>
+        namespace events {
+        class Dispatcher {
+        public:
+            void set_handler(std::function<bool(int32_t)> handler) {
+                handler_ = std::move(handler);
+            }
+            bool dispatch(int32_t event) {
+                return handler_ ? handler_(event) : false;
+            }
+            void clear() {
+                handler_ = nullptr;
+            }
+        private:
+            std::function<bool(int32_t)> handler_;
+        };
+        inline int32_t call_with(const std::function<int32_t(int32_t, int32_t)>& f, int32_t a, int32_t b) {
+            return f(a, b);
+        }
+        }
+    "};
+    let rs = quote! {
+        use std::sync::atomic::{AtomicI32, Ordering};
+        use std::sync::Arc;
+        let total = Arc::new(AtomicI32::new(0));
+        let seen = total.clone();
+        let mut dispatcher = ffi::events::Dispatcher::new().within_unique_ptr();
+        dispatcher.pin_mut().set_handler(move |event| {
+            seen.fetch_add(event, Ordering::SeqCst);
+            event > 2
+        });
+        assert!(!dispatcher.pin_mut().dispatch(1));
+        assert!(dispatcher.pin_mut().dispatch(3));
+        assert_eq!(total.load(Ordering::SeqCst), 4);
+        assert_eq!(Arc::strong_count(&total), 2);
+        // Dropping the std::function drops the closure.
+        dispatcher.pin_mut().clear();
+        assert_eq!(Arc::strong_count(&total), 1);
+        assert_eq!(ffi::events::call_with(|a, b| a * b, 6, 7), 42);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("events::Dispatcher")
+            generate!("events::call_with")
+            std_function!("events::Dispatcher::set_handler", "handler", (i32) -> bool)
+            std_function!("events::call_with", "f", (i32, i32) -> i32)
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_function_pointer_param() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        typedef int32_t (*binary_op)(int32_t, int32_t);
+        inline int32_t apply(int32_t (*op)(int32_t, int32_t), int32_t a, int32_t b) {
+            return op ? op(a, b) : -1;
+        }
+        inline int32_t apply_typedef(binary_op op, int32_t a, int32_t b) {
+            return op(a, b);
+        }
+    "};
+    let rs = quote! {
+        unsafe extern "C" fn add(a: i32, b: i32) -> i32 {
+            a + b
+        }
+        assert_eq!(unsafe { ffi::apply(Some(add), 2, 3) }, 5);
+        assert_eq!(unsafe { ffi::apply(None, 2, 3) }, -1);
+        assert_eq!(unsafe { ffi::apply_typedef(Some(add), 4, 5) }, 9);
+    };
+    run_test("", hdr, rs, &["apply", "apply_typedef"], &[]);
+}
+
+#[test]
+fn test_function_pointer_return() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        inline int32_t add(int32_t a, int32_t b) {
+            return a + b;
+        }
+        inline int32_t (*get_op(bool valid))(int32_t, int32_t) {
+            return valid ? add : nullptr;
+        }
+    "};
+    let rs = quote! {
+        let op = ffi::get_op(true).unwrap();
+        assert_eq!(unsafe { op(2, 3) }, 5);
+        assert!(ffi::get_op(false).is_none());
+    };
+    run_test("", hdr, rs, &["get_op"], &[]);
+}
+
+#[test]
+fn test_callback_closure() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        namespace events {
+        inline void for_each_up_to(int32_t n, void (*callback)(void*, int32_t), void* context) {
+            for (int32_t i = 0; i < n; i++) {
+                callback(context, i);
+            }
+        }
+        inline bool find_first(int32_t n, bool (*pred)(int32_t, void*), void* user_data) {
+            for (int32_t i = 0; i < n; i++) {
+                if (pred(i, user_data)) {
+                    return true;
+                }
+            }
+            return false;
+        }
+        }
+    "};
+    let rs = quote! {
+        let mut seen = Vec::new();
+        unsafe { ffi::events::for_each_up_to(4, |i| seen.push(i)) };
+        assert_eq!(seen, vec![0, 1, 2, 3]);
+        let mut calls = 0;
+        assert!(unsafe {
+            ffi::events::find_first(10, |i| {
+                calls += 1;
+                i == 2
+            })
+        });
+        assert_eq!(calls, 3);
+        assert!(!unsafe { ffi::events::find_first(2, |_| false) });
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("events::for_each_up_to")
+            generate!("events::find_first")
+            callback!("events::for_each_up_to", "callback", "context")
+            callback!("events::find_first", "pred", "user_data")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_callback_without_user_data() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        inline void call_twice(void (*callback)(int32_t), int32_t* counter) {
+            callback(*counter);
+            callback(*counter);
+        }
+    "};
+    let rs = quote! {
+        unsafe { ffi::call_twice(|_| {}) };
+    };
+    run_test_expect_fail_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("call_twice")
+            callback!("call_twice", "callback", "counter")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
 #[test]
 fn test_typedef_to_enum() {
     let hdr = indoc! {"
@@ -209,6 +209,19 @@ impl std::hash::Hash for ConcretesMap {
     }
 }
 
+/// Newtype wrapper so we can implement Hash.
+#[derive(Debug, Default)]
+pub struct DefaultParamsMap(pub HashMap<String, usize>);
+
+impl std::hash::Hash for DefaultParamsMap {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        for (k, v) in &self.0 {
+            k.hash(state);
+            v.hash(state);
+        }
+    }
+}
+
 #[derive(Debug, Default, Hash)]
 pub struct IncludeCppConfig {
     pub inclusions: Vec<String>,
@@ -228,6 +241,25 @@ pub struct IncludeCppConfig {
     pub concretes: ConcretesMap,
     pub externs: ExternCppTypeMap,
     pub opaquelist: Vec<String>,
+    pub default_params: DefaultParamsMap,
+    pub(crate) throwing_functions: Vec<String>,
+    pub(crate) total_order_types: Vec<String>,
+    pub(crate) hashable_types: Vec<String>,
+    pub(crate) field_accessor_types: Vec<String>,
+    pub(crate) out_params: Vec<(String, String)>,
+    pub(crate) variadic_instantiations: Vec<(String, Vec<syn::Type>)>,
+    pub(crate) callbacks: Vec<(String, String, String)>,
+    pub(crate) iterable_types: Vec<String>,
+    pub(crate) mutable_spans: Vec<(String, String)>,
+    pub(crate) std_functions: Vec<(String, String, Vec<syn::Type>, syn::ReturnType)>,
+    pub(crate) std_arrays: Vec<(String, String, syn::TypeArray)>,
+    pub(crate) concrete_methods: Vec<(
+        Ident,
+        String,
+        syn::Receiver,
+        Vec<syn::Type>,
+        syn::ReturnType,
+    )>,
 }
 
 impl Parse for IncludeCppConfig {
@@ -384,6 +416,127 @@ impl IncludeCppConfig {
         self.constructor_blocklist.contains(&cpp_name.to_string())
     }
 
+    /// How many trailing parameters of the given C++ function or method
+    /// have default values, if the user has told us.
+    pub fn num_default_params(&self, cpp_name: &str) -> Option<usize> {
+        self.default_params.0.get(cpp_name).cloned()
+    }
+
+    /// Whether the given C++ function or method was declared (using
+    /// `throws!`) to throw exceptions which should be reported to Rust.
+    pub fn is_throwing_function(&self, cpp_name: &str) -> bool {
+        self.throwing_functions.contains(&cpp_name.to_string())
+    }
+
+    /// Whether the given C++ type was declared (using `total_order!`) to
+    /// have comparison operators which form a total order.
+    pub fn is_total_order_type(&self, cpp_name: &str) -> bool {
+        self.total_order_types.contains(&cpp_name.to_string())
+    }
+
+    /// Whether the given C++ type was declared (using `hashable!`) to
+    /// have a `std::hash` specialization.
+    pub fn is_hashable_type(&self, cpp_name: &str) -> bool {
+        self.hashable_types.contains(&cpp_name.to_string())
+    }
+
+    /// Whether the given C++ type was listed in `field_accessors!`, so
+    /// should have accessors for its public fields even though it's not POD.
+    pub fn wants_field_accessors(&self, cpp_name: &str) -> bool {
+        self.field_accessor_types.contains(&cpp_name.to_string())
+    }
+
+    /// Whether the given parameter of the given C++ function or method was
+    /// declared (using `out_param!`) to be an out-param.
+    pub fn is_out_param(&self, cpp_name: &str, param: &str) -> bool {
+        self.out_params
+            .iter()
+            .any(|(function, p)| function == cpp_name && p == param)
+    }
+
+    /// Whether the given C++ type was declared (using `iterable!`) to
+    /// have iterators which yield references to its items.
+    pub fn is_iterable_type(&self, cpp_name: &str) -> bool {
+        self.iterable_types.contains(&cpp_name.to_string())
+    }
+
+    /// Whether the given parameter of the given C++ function or method was
+    /// declared (using `mutable_span!`) to be a span of non-const items.
+    pub fn is_mutable_span(&self, cpp_name: &str, param: &str) -> bool {
+        self.mutable_spans
+            .iter()
+            .any(|(function, p)| function == cpp_name && p == param)
+    }
+
+    /// If the given parameter of the given C++ function was declared (using
+    /// `std_function!`) to be a `std::function`, the types of its
+    /// parameters and its return type.
+    pub fn std_function_signature(
+        &self,
+        cpp_name: &str,
+        param: &str,
+    ) -> Option<(&[syn::Type], &syn::ReturnType)> {
+        self.std_functions
+            .iter()
+            .find(|(function, p, _, _)| function == cpp_name && p == param)
+            .map(|(_, _, types, output)| (types.as_slice(), output))
+    }
+
+    /// If the given parameter of the given C++ function, or its return
+    /// value if `param` is `return`, was declared (using `std_array!`) to
+    /// be a `std::array`, the corresponding Rust array type.
+    pub fn std_array_type(&self, cpp_name: &str, param: &str) -> Option<&syn::TypeArray> {
+        self.std_arrays
+            .iter()
+            .find(|(function, p, _)| function == cpp_name && p == param)
+            .map(|(_, _, array)| array)
+    }
+
+    /// If the given parameter of the given C++ function was declared (using
+    /// `callback!`) to be a callback, the name of the parameter which is
+    /// passed back to it as user data.
+    pub fn callback_user_data(&self, cpp_name: &str, param: &str) -> Option<&str> {
+        self.callbacks
+            .iter()
+            .find(|(function, callback, _)| function == cpp_name && callback == param)
+            .map(|(_, _, user_data)| user_data.as_str())
+    }
+
+    /// The parameter types of each fixed-arity instantiation of the given
+    /// variadic C++ function requested using `variadic!`, in order. These
+    /// are the types of the variadic arguments only.
+    pub fn variadic_instantiations<'a>(
+        &'a self,
+        cpp_name: &'a str,
+    ) -> impl Iterator<Item = &'a [syn::Type]> + 'a {
+        self.variadic_instantiations
+            .iter()
+            .filter(move |(function, _)| function == cpp_name)
+            .map(|(_, types)| types.as_slice())
+    }
+
+    /// The methods declared using `concrete_method!` for the concrete
+    /// type with the given Rust name: for each, its C++ name, its receiver,
+    /// and its parameter and return types.
+    pub fn concrete_methods<'a>(
+        &'a self,
+        rust_name: &'a str,
+    ) -> impl Iterator<
+        Item = (
+            &'a str,
+            &'a syn::Receiver,
+            &'a [syn::Type],
+            &'a syn::ReturnType,
+        ),
+    > + 'a {
+        self.concrete_methods
+            .iter()
+            .filter(move |(concrete, _, _, _, _)| concrete == rust_name)
+            .map(|(_, method, receiver, types, output)| {
+                (method.as_str(), receiver, types.as_slice(), output)
+            })
+    }
+
     pub fn get_blocklist(&self) -> impl Iterator<Item = &String> {
         self.blocklist.iter()
     }
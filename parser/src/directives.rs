@@ -16,7 +16,7 @@ use proc_macro2::TokenStream;
 
 #[cfg(feature = "reproduction_case")]
 use quote::{quote, ToTokens};
-use syn::parse::ParseStream;
+use syn::parse::{Parse, ParseStream};
 
 use crate::config::AllowlistErr;
 
@@ -72,6 +72,41 @@ pub(crate) fn get_directives() -> &'static DirectivesMap {
                 |config| &config.constructor_blocklist,
             )),
         );
+        need_exclamation.insert(
+            "throws".into(),
+            Box::new(StringList(
+                |config| &mut config.throwing_functions,
+                |config| &config.throwing_functions,
+            )),
+        );
+        need_exclamation.insert(
+            "total_order".into(),
+            Box::new(StringList(
+                |config| &mut config.total_order_types,
+                |config| &config.total_order_types,
+            )),
+        );
+        need_exclamation.insert(
+            "hashable".into(),
+            Box::new(StringList(
+                |config| &mut config.hashable_types,
+                |config| &config.hashable_types,
+            )),
+        );
+        need_exclamation.insert(
+            "field_accessors".into(),
+            Box::new(StringList(
+                |config| &mut config.field_accessor_types,
+                |config| &config.field_accessor_types,
+            )),
+        );
+        need_exclamation.insert(
+            "iterable".into(),
+            Box::new(StringList(
+                |config| &mut config.iterable_types,
+                |config| &config.iterable_types,
+            )),
+        );
         need_exclamation.insert(
             "instantiable".into(),
             Box::new(StringList(
@@ -102,6 +137,26 @@ pub(crate) fn get_directives() -> &'static DirectivesMap {
         );
         need_exclamation.insert("name".into(), Box::new(ModName));
         need_exclamation.insert("concrete".into(), Box::new(Concrete));
+        need_exclamation.insert("concrete_method".into(), Box::new(ConcreteMethod));
+        need_exclamation.insert("default_params".into(), Box::new(DefaultParams));
+        need_exclamation.insert(
+            "out_param".into(),
+            Box::new(ParamList(
+                |config| &mut config.out_params,
+                |config| &config.out_params,
+            )),
+        );
+        need_exclamation.insert(
+            "mutable_span".into(),
+            Box::new(ParamList(
+                |config| &mut config.mutable_spans,
+                |config| &config.mutable_spans,
+            )),
+        );
+        need_exclamation.insert("callback".into(), Box::new(Callback));
+        need_exclamation.insert("std_function".into(), Box::new(StdFunction));
+        need_exclamation.insert("std_array".into(), Box::new(StdArray));
+        need_exclamation.insert("variadic".into(), Box::new(Variadic));
         need_exclamation.insert("rust_type".into(), Box::new(RustType { output: false }));
         need_exclamation.insert(EXTERN_RUST_TYPE.into(), Box::new(RustType { output: true }));
         need_exclamation.insert(SUBCLASS.into(), Box::new(Subclass));
@@ -422,6 +477,266 @@ impl Directive for Concrete {
     }
 }
 
+struct ConcreteMethod;
+
+impl Directive for ConcreteMethod {
+    fn parse(
+        &self,
+        args: ParseStream,
+        config: &mut IncludeCppConfig,
+        _ident_span: &Span,
+    ) -> ParseResult<()> {
+        let concrete: syn::Ident = args.parse()?;
+        args.parse::<syn::token::Comma>()?;
+        let method: syn::LitStr = args.parse()?;
+        args.parse::<syn::token::Comma>()?;
+        let content;
+        syn::parenthesized!(content in args);
+        let receiver: syn::Receiver = content.parse()?;
+        if receiver.reference.is_none() {
+            return Err(syn::Error::new_spanned(
+                receiver,
+                "methods of concrete types must take &self or &mut self",
+            ));
+        }
+        let types = if content.is_empty() {
+            Vec::new()
+        } else {
+            content.parse::<syn::token::Comma>()?;
+            content
+                .parse_terminated(syn::Type::parse, syn::token::Comma)?
+                .into_iter()
+                .collect()
+        };
+        let output: syn::ReturnType = args.parse()?;
+        config
+            .concrete_methods
+            .push((concrete, method.value(), receiver, types, output));
+        Ok(())
+    }
+
+    #[cfg(feature = "reproduction_case")]
+    fn output<'a>(
+        &self,
+        config: &'a IncludeCppConfig,
+    ) -> Box<dyn Iterator<Item = TokenStream> + 'a> {
+        Box::new(config.concrete_methods.iter().map(
+            |(concrete, method, receiver, types, output)| {
+                quote! {
+                    #concrete,#method,(#receiver #(,#types)*) #output
+                }
+            },
+        ))
+    }
+}
+
+struct DefaultParams;
+
+impl Directive for DefaultParams {
+    fn parse(
+        &self,
+        args: ParseStream,
+        config: &mut IncludeCppConfig,
+        _ident_span: &Span,
+    ) -> ParseResult<()> {
+        let function: syn::LitStr = args.parse()?;
+        args.parse::<syn::token::Comma>()?;
+        let count: syn::LitInt = args.parse()?;
+        config
+            .default_params
+            .0
+            .insert(function.value(), count.base10_parse()?);
+        Ok(())
+    }
+
+    #[cfg(feature = "reproduction_case")]
+    fn output<'a>(
+        &self,
+        config: &'a IncludeCppConfig,
+    ) -> Box<dyn Iterator<Item = TokenStream> + 'a> {
+        Box::new(config.default_params.0.iter().map(|(k, v)| {
+            let v = proc_macro2::Literal::usize_unsuffixed(*v);
+            quote! {
+                #k,#v
+            }
+        }))
+    }
+}
+
+struct ParamList<SET, GET>(SET, GET)
+where
+    SET: Fn(&mut IncludeCppConfig) -> &mut Vec<(String, String)>,
+    GET: Fn(&IncludeCppConfig) -> &Vec<(String, String)>;
+
+impl<SET, GET> Directive for ParamList<SET, GET>
+where
+    SET: Fn(&mut IncludeCppConfig) -> &mut Vec<(String, String)> + Sync + Send,
+    GET: Fn(&IncludeCppConfig) -> &Vec<(String, String)> + Sync + Send,
+{
+    fn parse(
+        &self,
+        args: ParseStream,
+        config: &mut IncludeCppConfig,
+        _ident_span: &Span,
+    ) -> ParseResult<()> {
+        let function: syn::LitStr = args.parse()?;
+        args.parse::<syn::token::Comma>()?;
+        let param: syn::LitStr = args.parse()?;
+        self.0(config).push((function.value(), param.value()));
+        Ok(())
+    }
+
+    #[cfg(feature = "reproduction_case")]
+    fn output<'a>(
+        &self,
+        config: &'a IncludeCppConfig,
+    ) -> Box<dyn Iterator<Item = TokenStream> + 'a> {
+        Box::new(self.1(config).iter().map(|(k, v)| {
+            quote! {
+                #k,#v
+            }
+        }))
+    }
+}
+
+struct Callback;
+
+impl Directive for Callback {
+    fn parse(
+        &self,
+        args: ParseStream,
+        config: &mut IncludeCppConfig,
+        _ident_span: &Span,
+    ) -> ParseResult<()> {
+        let function: syn::LitStr = args.parse()?;
+        args.parse::<syn::token::Comma>()?;
+        let callback: syn::LitStr = args.parse()?;
+        args.parse::<syn::token::Comma>()?;
+        let user_data: syn::LitStr = args.parse()?;
+        config
+            .callbacks
+            .push((function.value(), callback.value(), user_data.value()));
+        Ok(())
+    }
+
+    #[cfg(feature = "reproduction_case")]
+    fn output<'a>(
+        &self,
+        config: &'a IncludeCppConfig,
+    ) -> Box<dyn Iterator<Item = TokenStream> + 'a> {
+        Box::new(config.callbacks.iter().map(|(f, c, u)| {
+            quote! {
+                #f,#c,#u
+            }
+        }))
+    }
+}
+
+struct StdFunction;
+
+impl Directive for StdFunction {
+    fn parse(
+        &self,
+        args: ParseStream,
+        config: &mut IncludeCppConfig,
+        _ident_span: &Span,
+    ) -> ParseResult<()> {
+        let function: syn::LitStr = args.parse()?;
+        args.parse::<syn::token::Comma>()?;
+        let param: syn::LitStr = args.parse()?;
+        args.parse::<syn::token::Comma>()?;
+        let content;
+        syn::parenthesized!(content in args);
+        let types = content.parse_terminated(syn::Type::parse, syn::token::Comma)?;
+        let output: syn::ReturnType = args.parse()?;
+        config.std_functions.push((
+            function.value(),
+            param.value(),
+            types.into_iter().collect(),
+            output,
+        ));
+        Ok(())
+    }
+
+    #[cfg(feature = "reproduction_case")]
+    fn output<'a>(
+        &self,
+        config: &'a IncludeCppConfig,
+    ) -> Box<dyn Iterator<Item = TokenStream> + 'a> {
+        Box::new(config.std_functions.iter().map(|(f, p, types, output)| {
+            quote! {
+                #f,#p,(#(#types),*) #output
+            }
+        }))
+    }
+}
+
+struct StdArray;
+
+impl Directive for StdArray {
+    fn parse(
+        &self,
+        args: ParseStream,
+        config: &mut IncludeCppConfig,
+        _ident_span: &Span,
+    ) -> ParseResult<()> {
+        let function: syn::LitStr = args.parse()?;
+        args.parse::<syn::token::Comma>()?;
+        let param: syn::LitStr = args.parse()?;
+        args.parse::<syn::token::Comma>()?;
+        let array: syn::TypeArray = args.parse()?;
+        config
+            .std_arrays
+            .push((function.value(), param.value(), array));
+        Ok(())
+    }
+
+    #[cfg(feature = "reproduction_case")]
+    fn output<'a>(
+        &self,
+        config: &'a IncludeCppConfig,
+    ) -> Box<dyn Iterator<Item = TokenStream> + 'a> {
+        Box::new(config.std_arrays.iter().map(|(f, p, array)| {
+            quote! {
+                #f,#p,#array
+            }
+        }))
+    }
+}
+
+struct Variadic;
+
+impl Directive for Variadic {
+    fn parse(
+        &self,
+        args: ParseStream,
+        config: &mut IncludeCppConfig,
+        _ident_span: &Span,
+    ) -> ParseResult<()> {
+        let function: syn::LitStr = args.parse()?;
+        args.parse::<syn::token::Comma>()?;
+        let content;
+        syn::parenthesized!(content in args);
+        let types = content.parse_terminated(syn::Type::parse, syn::token::Comma)?;
+        config
+            .variadic_instantiations
+            .push((function.value(), types.into_iter().collect()));
+        Ok(())
+    }
+
+    #[cfg(feature = "reproduction_case")]
+    fn output<'a>(
+        &self,
+        config: &'a IncludeCppConfig,
+    ) -> Box<dyn Iterator<Item = TokenStream> + 'a> {
+        Box::new(config.variadic_instantiations.iter().map(|(k, v)| {
+            quote! {
+                #k,(#(#v),*)
+            }
+        }))
+    }
+}
+
 struct RustType {
     #[allow(dead_code)]
     output: bool,
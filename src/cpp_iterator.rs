@@ -0,0 +1,93 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::{marker::PhantomData, pin::Pin};
+
+use cxx::{memory::UniquePtrTarget, UniquePtr};
+
+/// The state of an iteration over a C++ range, i.e. a pair of C++
+/// iterators obtained from `begin()` and `end()`.
+///
+/// `autocxx` implements this for the types it generates for any C++ type
+/// with `begin()` and `end()` methods, and then uses it to implement
+/// `IntoIterator` for references to that type. It is not recommended that
+/// you implement this trait, nor that you use it directly.
+///
+/// # Safety
+///
+/// Implementations must return either null, or a pointer to an item in the
+/// range, which must remain valid for as long as the range is borrowed.
+pub unsafe trait CppIteratorState: UniquePtrTarget {
+    /// The type of each item in the range.
+    type Item;
+
+    /// Return a pointer to the current item and advance to the next, or
+    /// return null if we've reached the end.
+    fn next_item(self: Pin<&mut Self>) -> *const Self::Item;
+}
+
+/// An iterator over a C++ range, yielding references to each item.
+/// You'll get one of these by calling `into_iter()` on a reference to
+/// a C++ type with `begin()` and `end()` methods, or by using such a
+/// reference in a `for` loop.
+pub struct CppIterator<'a, S: CppIteratorState> {
+    state: UniquePtr<S>,
+    phantom: PhantomData<&'a S::Item>,
+}
+
+impl<S: CppIteratorState> CppIterator<'_, S> {
+    #[doc(hidden)]
+    pub fn new(state: UniquePtr<S>) -> Self {
+        Self {
+            state,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, S: CppIteratorState> Iterator for CppIterator<'a, S> {
+    type Item = &'a S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Safety: the C++ range is borrowed for 'a.
+        unsafe { self.state.pin_mut().next_item().as_ref() }
+    }
+}
+
+/// An iterator over a C++ range, yielding pinned mutable references to
+/// each item. You'll get one of these by calling `into_iter()` on a
+/// `Pin<&mut T>` where `T` is a C++ type with non-const `begin()` and `end()`
+/// methods.
+pub struct CppIteratorMut<'a, S: CppIteratorState> {
+    state: UniquePtr<S>,
+    phantom: PhantomData<&'a mut S::Item>,
+}
+
+impl<S: CppIteratorState> CppIteratorMut<'_, S> {
+    #[doc(hidden)]
+    pub fn new(state: UniquePtr<S>) -> Self {
+        Self {
+            state,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, S: CppIteratorState> Iterator for CppIteratorMut<'a, S> {
+    type Item = Pin<&'a mut S::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Safety: the C++ range is mutably borrowed for 'a, and each item
+        // is yielded only once. C++ objects are never moved by Rust.
+        unsafe {
+            (self.state.pin_mut().next_item() as *mut S::Item)
+                .as_mut()
+                .map(|item| Pin::new_unchecked(item))
+        }
+    }
+}
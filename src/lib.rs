@@ -17,11 +17,13 @@
 // do anything - all the magic is handled entirely by
 // autocxx_macro::include_cpp_impl.
 
+mod cpp_iterator;
 mod reference_wrapper;
 mod rvalue_param;
 pub mod subclass;
 mod value_param;
 
+pub use cpp_iterator::{CppIterator, CppIteratorMut, CppIteratorState};
 pub use reference_wrapper::{
     AsCppMutRef, AsCppRef, CppLtRef, CppMutLtRef, CppMutRef, CppPin, CppRef, CppUniquePtrPin,
 };
@@ -261,6 +263,179 @@ macro_rules! concrete {
     ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
 }
 
+/// Declares a method of a concrete type made with [concrete], for example
+/// `concrete_method!(ContainerOfU32, "get", (&self) -> u32)`. bindgen
+/// doesn't tell us about the methods of class templates, so you need
+/// to list the receiver and the parameter and return types. autocxx will
+/// generate a C++ wrapper which calls the method.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! concrete_method {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Declares that some trailing parameters of a C++ function or
+/// method have default values, for example
+/// `default_params!("ns::Widget::resize", 2)`. autocxx will generate
+/// additional entry points which omit those parameters, letting the
+/// C++ compiler fill in the defaults. Constructors are named like
+/// `"ns::Widget::Widget"`.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! default_params {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Declares that a `std::span` parameter of a C++ function or method
+/// refers to items which the C++ may modify, for example
+/// `mutable_span!("ns::fill", "values")`. The Rust binding will take a
+/// `&mut [T]` for it rather than a `&[T]`.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! mutable_span {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Declares the signature of a `std::function` parameter of a C++ function,
+/// for example `std_function!("ns::set_handler", "handler", (i32) -> bool)`.
+/// autocxx can't otherwise discover the signature, so can't generate the
+/// function. The Rust binding will take a closure instead, which is
+/// dropped when the last copy of the C++ `std::function` is destroyed.
+/// Calls to the closure are serialized with a mutex, so a closure which
+/// causes the C++ to call it again will deadlock.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! std_function {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Declares the element type and size of a `std::array` parameter of a C++
+/// function, for example `std_array!("ns::set_matrix", "m", [f32; 16])`,
+/// or of its return value, for example
+/// `std_array!("ns::get_matrix", "return", [f32; 16])`. autocxx can't
+/// otherwise discover them, so can't generate the function. The Rust
+/// binding will use the given array type instead.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! std_array {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Declares that a parameter of a C++ function or method is an out-param,
+/// for example `out_param!("ns::create_widget", "out")`. The parameter
+/// must be a pointer to a pointer, such as `Widget** out`. Instead of
+/// taking it, the Rust binding will return the object which the C++
+/// puts there as a `UniquePtr`, after the C++ return value, if any.
+/// The object therefore must be one which can be freed using `delete`.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! out_param {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Declares that a C function pointer parameter of a C++ function is a
+/// callback, along with the `void*` parameter which is passed back to it
+/// as user data, for example
+/// `callback!("ns::for_each_item", "callback", "context")`. The Rust
+/// binding will instead take a closure, which is called each time the C++
+/// calls the callback. The C++ must not keep the callback or user data
+/// to call after the function returns, so the binding is `unsafe`.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! callback {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Declares a fixed-arity instantiation of a variadic C or C++ function,
+/// for example `variadic!("log_printf", (i32, *const c_char))`, which lists
+/// the types to be passed in place of the `...`. autocxx will generate a
+/// C++ wrapper with that signature which forwards to the variadic function.
+/// Repeat the directive to get several instantiations; as with overloads,
+/// all but the first will have a numeric suffix.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! variadic {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Declares that a C++ function or method may throw exceptions, for
+/// example `throws!("ns::Parser::parse")`. Its bindings will return
+/// a `Result<T, cxx::Exception>` instead of letting the exception
+/// unwind into Rust.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! throws {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Declares that the comparison operators of a C++ type form a total
+/// order, for example `total_order!("ns::Version")`. If the type has an
+/// `operator<` or `operator<=>`, the Rust type will then implement
+/// [`Ord`] and [`Eq`] as well as [`PartialOrd`], so it can be used as
+/// the key of a `BTreeMap`. autocxx does this anyway for an `operator<=>`
+/// returning `std::strong_ordering`.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! total_order {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Declares that a C++ type has a `std::hash` specialization, for
+/// example `hashable!("ns::Point")`. The Rust type will then implement
+/// [`Hash`](std::hash::Hash) using it.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! hashable {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Declares that dereferencing the iterators returned by the `begin()`
+/// and `end()` methods of a C++ type gives a reference to an item, for
+/// example `iterable!("ns::Bag")`. The Rust type will then implement
+/// [`IntoIterator`]. This isn't needed if `begin()` returns a pointer.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! iterable {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Asks for accessors for the public fields of a non-POD C++ type, for
+/// example `field_accessors!("ns::Person")`. Each field `name` gets a
+/// `name()` method returning a reference to it, and a `name_mut()` method
+/// returning a pinned mutable reference. (POD types don't need this, since
+/// their fields can be used directly.)
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! field_accessors {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
 /// Specifies a global safety policy for functions generated
 /// from these headers. By default (without such a `safety!`
 /// directive) all such functions are marked as `unsafe` and